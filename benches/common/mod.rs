@@ -0,0 +1,136 @@
+//! Seeded generators of puzzle-like inputs of any size, shared by the benches.
+
+#![allow(dead_code)]
+
+use advent_of_code::day02::Entry;
+use criterion::{measurement::WallTime, BenchmarkGroup};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::env;
+
+/// Set to run the benches on 10M lines as well, which needs several hundred
+/// MB of memory per input and takes a while.
+pub const LARGE_VAR: &str = "AOC_BENCH_LARGE";
+
+const SEED: u64 = 2020;
+
+/// Numbers of lines to bench with: 1k and 100k, and 10M with `LARGE_VAR`.
+pub fn sizes() -> Vec<usize> {
+    let mut sizes = vec![1_000, 100_000];
+    if env::var_os(LARGE_VAR).is_some() {
+        sizes.push(10_000_000);
+    }
+    sizes
+}
+
+/// Takes fewer samples of the larger sizes, so a run finishes in minutes.
+pub fn configure(group: &mut BenchmarkGroup<WallTime>, size: usize) {
+    group.sample_size(if size >= 100_000 { 10 } else { 100 });
+}
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(SEED)
+}
+
+/// `n` expense entries holding the entries of the puzzle example at random
+/// positions, so exactly one pair and one triplet sum to 2020. All other
+/// entries are larger than 2020 and never part of a solution.
+pub fn day01(n: usize) -> Vec<i32> {
+    const EXAMPLE: [i32; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    let mut rng = rng();
+    let mut entries: Vec<i32> = (0..n.max(EXAMPLE.len()) - EXAMPLE.len())
+        .map(|_| rng.gen_range(2021..=1_000_000))
+        .collect();
+    for entry in EXAMPLE.iter() {
+        let pos = rng.gen_range(0..=entries.len());
+        entries.insert(pos, *entry);
+    }
+    entries
+}
+
+/// `n` expense entries below 2020, all odd so no three of them sum to 2020.
+/// With `solvable`, one of them is replaced by 366, making every two odd
+/// entries summing to 1654 a triplet with it.
+pub fn day01_in_range(n: usize, solvable: bool) -> Vec<i32> {
+    let mut rng = rng();
+    let mut entries: Vec<i32> = (0..n).map(|_| rng.gen_range(0..1010) * 2 + 1).collect();
+    if solvable && n > 0 {
+        let pos = rng.gen_range(0..n);
+        entries[pos] = 366;
+    }
+    entries
+}
+
+/// `n` password entries with short random passwords, about half of them valid
+/// under each policy.
+pub fn day02(n: usize) -> Vec<Entry> {
+    let mut rng = rng();
+    (0..n)
+        .map(|_| {
+            let pos1 = rng.gen_range(1..=5);
+            let pos2 = rng.gen_range(pos1 + 1..=12);
+            let len = rng.gen_range(pos2..=20);
+            Entry {
+                pos1,
+                pos2,
+                c: rng.gen_range(b'a'..=b'e') as char,
+                pwd: (0..len)
+                    .map(|_| rng.gen_range(b'a'..=b'e') as char)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// A map of `n` lines, 31 tiles wide like the real input, with about a
+/// quarter of the tiles being trees.
+pub fn day03(n: usize) -> Vec<String> {
+    let mut rng = rng();
+    (0..n)
+        .map(|_| {
+            (0..31)
+                .map(|_| if rng.gen_bool(0.25) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+/// Passports spread over `n` lines in total, each with its fields on one to
+/// four lines. Some miss a required field and some have invalid values.
+pub fn day04(n: usize) -> Vec<String> {
+    const ECL: [&str; 8] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth", "red"];
+
+    let mut rng = rng();
+    let mut passports = Vec::new();
+    let mut lines = 0;
+    while lines < n {
+        let mut fields = vec![
+            format!("byr:{}", rng.gen_range(1900..=2010)),
+            format!("iyr:{}", rng.gen_range(2005..=2025)),
+            format!("eyr:{}", rng.gen_range(2015..=2035)),
+            if rng.gen_bool(0.5) {
+                format!("hgt:{}cm", rng.gen_range(140..=200))
+            } else {
+                format!("hgt:{}in", rng.gen_range(55..=80))
+            },
+            format!("hcl:#{:06x}", rng.gen_range(0..0x100_0000)),
+            format!("ecl:{}", ECL[rng.gen_range(0..ECL.len())]),
+            format!("pid:{:09}", rng.gen_range(0..1_000_000_000)),
+        ];
+        if rng.gen_bool(0.5) {
+            fields.push(format!("cid:{}", rng.gen_range(1..=255)));
+        }
+        if rng.gen_bool(0.1) {
+            let missing = rng.gen_range(0..7);
+            fields.remove(missing);
+        }
+
+        let passport_lines = rng.gen_range(1..=4).min(n - lines);
+        let per_line = fields.len().div_ceil(passport_lines);
+        let passport: Vec<String> = fields.chunks(per_line).map(|c| c.join(" ")).collect();
+        lines += passport.len();
+        passports.push(passport.join("\n"));
+    }
+    passports
+}
@@ -0,0 +1,69 @@
+use advent_of_code::day01::{find_pair, find_triplet_with, TripletImpl};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod common;
+
+/// `TripletImpl::Hash` copies the rest of the input for every entry it tries,
+/// so searching inputs without a triplet above this size takes minutes per
+/// iteration.
+const MAX_HASH_NONE_SIZE: usize = 10_000;
+
+fn bench_find_pair(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day01/find_pair");
+    for size in common::sizes() {
+        let input = common::day01(size);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| find_pair(black_box(input), 2020))
+        });
+    }
+    group.finish();
+}
+
+/// Benches the triplet searches on entries within the range of the target,
+/// `solvable` ones having many triplets and the others none at all.
+fn bench_find_triplet(c: &mut Criterion, solvable: bool) {
+    let impls = [
+        ("hash", TripletImpl::Hash),
+        ("sorted", TripletImpl::Sorted),
+        ("bitset", TripletImpl::Bitset),
+    ];
+
+    let name = if solvable {
+        "day01/find_triplet"
+    } else {
+        "day01/find_triplet_none"
+    };
+    let mut group = c.benchmark_group(name);
+    for size in [10_000, 100_000, 1_000_000] {
+        let input = common::day01_in_range(size, solvable);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        for (name, imp) in impls.iter() {
+            if *imp == TripletImpl::Hash && !solvable && size > MAX_HASH_NONE_SIZE {
+                continue;
+            }
+            group.bench_with_input(BenchmarkId::new(*name, size), &input, |b, input| {
+                b.iter(|| find_triplet_with(black_box(input), 2020, *imp))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_find_triplet_solvable(c: &mut Criterion) {
+    bench_find_triplet(c, true);
+}
+
+fn bench_find_triplet_none(c: &mut Criterion) {
+    bench_find_triplet(c, false);
+}
+
+criterion_group!(
+    benches,
+    bench_find_pair,
+    bench_find_triplet_solvable,
+    bench_find_triplet_none
+);
+criterion_main!(benches);
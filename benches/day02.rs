@@ -0,0 +1,41 @@
+use advent_of_code::day02::{is_valid_password, is_valid_toboggan_password};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod common;
+
+fn bench_validators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day02/is_valid_password");
+    for size in common::sizes() {
+        let entries = common::day02(size);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &entries, |b, entries| {
+            b.iter(|| {
+                black_box(entries)
+                    .iter()
+                    .filter(|e| is_valid_password(e.pos1, e.pos2, e.c, &e.pwd))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("day02/is_valid_toboggan_password");
+    for size in common::sizes() {
+        let entries = common::day02(size);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &entries, |b, entries| {
+            b.iter(|| {
+                black_box(entries)
+                    .iter()
+                    .filter(|e| is_valid_toboggan_password(e.pos1, e.pos2, e.c, &e.pwd))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_validators);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+use advent_of_code::day03::traverse;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod common;
+
+fn bench_traverse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day03/traverse");
+    for size in common::sizes() {
+        let slope = common::day03(size);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &slope, |b, slope| {
+            b.iter(|| traverse(black_box(slope), (0, 0), (3, 1)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_traverse);
+criterion_main!(benches);
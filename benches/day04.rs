@@ -0,0 +1,48 @@
+use advent_of_code::day04::Passport;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::str::FromStr;
+
+mod common;
+
+fn bench_from_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day04/from_str");
+    for size in common::sizes() {
+        let passports = common::day04(size);
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &passports,
+            |b, passports| {
+                b.iter(|| {
+                    black_box(passports)
+                        .iter()
+                        .filter_map(|p| Passport::from_str(p).ok())
+                        .count()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_is_valid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day04/is_valid");
+    for size in common::sizes() {
+        let passports: Vec<Passport> = common::day04(size)
+            .iter()
+            .filter_map(|p| Passport::from_str(p).ok())
+            .collect();
+        common::configure(&mut group, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &passports,
+            |b, passports| b.iter(|| black_box(passports).iter().filter(|p| p.is_valid()).count()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_from_str, bench_is_valid);
+criterion_main!(benches);
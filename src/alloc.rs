@@ -0,0 +1,207 @@
+use std::fmt;
+
+/// Allocations made during a phase, counted on the thread running it.
+///
+/// Only recorded with the `alloc-stats` feature, which installs a counting
+/// global allocator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocs: u64,
+    pub bytes: u64,
+    /// Most bytes held at once above what was held when the phase started.
+    pub peak: u64,
+}
+
+impl AllocStats {
+    /// Adds the stats of another run of the same phase.
+    pub fn merge(&mut self, other: AllocStats) {
+        self.allocs += other.allocs;
+        self.bytes += other.bytes;
+        self.peak = self.peak.max(other.peak);
+    }
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, peak {}",
+            self.allocs,
+            fmt_bytes(self.bytes),
+            fmt_bytes(self.peak)
+        )
+    }
+}
+
+/// Formats `bytes` in the largest binary unit it fills, like `1.5 KiB`.
+pub fn fmt_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Whether allocations are being counted.
+pub const fn enabled() -> bool {
+    cfg!(feature = "alloc-stats")
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    thread_local! {
+        static ALLOCS: Cell<u64> = const { Cell::new(0) };
+        static BYTES: Cell<u64> = const { Cell::new(0) };
+        static CURRENT: Cell<i64> = const { Cell::new(0) };
+        static PEAK: Cell<i64> = const { Cell::new(0) };
+    }
+
+    struct Counting;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn record(allocated: usize, freed: usize) {
+        // Counting is skipped while the thread is being torn down.
+        let _ = CURRENT.try_with(|current| {
+            if allocated > 0 {
+                ALLOCS.with(|n| n.set(n.get() + 1));
+                BYTES.with(|n| n.set(n.get() + allocated as u64));
+            }
+            let now = current.get() + allocated as i64 - freed as i64;
+            current.set(now);
+            PEAK.with(|peak| peak.set(peak.get().max(now)));
+        });
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            record(layout.size(), 0);
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            record(layout.size(), 0);
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            record(0, layout.size());
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            record(new_size, layout.size());
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    /// The counters when a phase started.
+    pub struct Mark {
+        allocs: u64,
+        bytes: u64,
+        current: i64,
+        outer_peak: i64,
+    }
+
+    pub fn start() -> Mark {
+        let current = CURRENT.with(Cell::get);
+        Mark {
+            allocs: ALLOCS.with(Cell::get),
+            bytes: BYTES.with(Cell::get),
+            current,
+            outer_peak: PEAK.with(|peak| peak.replace(current)),
+        }
+    }
+
+    pub fn stop(mark: Mark) -> super::AllocStats {
+        let peak = PEAK.with(|peak| {
+            let phase_peak = peak.get();
+            peak.set(phase_peak.max(mark.outer_peak));
+            phase_peak
+        });
+        super::AllocStats {
+            allocs: ALLOCS.with(Cell::get) - mark.allocs,
+            bytes: BYTES.with(Cell::get) - mark.bytes,
+            peak: (peak - mark.current).max(0) as u64,
+        }
+    }
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+mod counting {
+    pub struct Mark;
+
+    pub fn start() -> Mark {
+        Mark
+    }
+
+    pub fn stop(_: Mark) -> super::AllocStats {
+        super::AllocStats::default()
+    }
+}
+
+pub(crate) use counting::{start, stop, Mark};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_bytes() {
+        assert_eq!(fmt_bytes(512), "512 B");
+        assert_eq!(fmt_bytes(1536), "1.5 KiB");
+        assert_eq!(fmt_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut stats = AllocStats {
+            allocs: 2,
+            bytes: 100,
+            peak: 80,
+        };
+        stats.merge(AllocStats {
+            allocs: 1,
+            bytes: 50,
+            peak: 50,
+        });
+
+        assert_eq!(
+            stats,
+            AllocStats {
+                allocs: 3,
+                bytes: 150,
+                peak: 80
+            }
+        );
+    }
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn test_counting() {
+        let outer = start();
+        let kept: Vec<u8> = Vec::with_capacity(1000);
+        let inner = start();
+        drop(vec![0u8; 4000]);
+        let inner = stop(inner);
+        let outer = stop(outer);
+        drop(kept);
+
+        assert_eq!(inner.allocs, 1);
+        assert_eq!(inner.bytes, 4000);
+        assert_eq!(inner.peak, 4000);
+        assert_eq!(outer.allocs, 2);
+        assert_eq!(outer.peak, 5000);
+    }
+}
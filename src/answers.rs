@@ -0,0 +1,160 @@
+use crate::input::input_dir;
+use crate::{DayResult, Error, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Accepted answers, keyed by day and part.
+///
+/// The answers file has one `<day> <part> <answer>` line per solved part,
+/// blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u8, u8), String>);
+
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {:02} part {}:\n  - expected: {}\n  + actual:   {}",
+            self.day, self.part, self.expected, self.actual
+        )
+    }
+}
+
+/// `answers.txt`, next to the input directory.
+pub fn answers_path() -> PathBuf {
+    input_dir().with_file_name("answers.txt")
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers> {
+        let content = fs::read_to_string(path).map_err(|cause| Error::io(path, cause))?;
+        content.parse()
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Compares the answers in `result` against the accepted ones. Parts
+    /// without an accepted answer are not checked.
+    pub fn check(&self, result: &DayResult) -> Vec<Mismatch> {
+        result
+            .parts
+            .iter()
+            .filter_map(|p| {
+                let expected = self.get(result.day, p.part)?;
+                let actual = p.answer_text();
+                if expected == actual {
+                    return None;
+                }
+                Some(Mismatch {
+                    day: result.day,
+                    part: p.part,
+                    expected: expected.to_string(),
+                    actual,
+                })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Answers> {
+        let mut answers = BTreeMap::new();
+
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, ' ');
+            let day = fields.next().and_then(|d| d.parse::<u8>().ok());
+            let part = fields.next().and_then(|p| p.parse::<u8>().ok());
+            match (day, part, fields.next()) {
+                (Some(day), Some(part), Some(answer)) => {
+                    answers.insert((day, part), answer.trim().to_string());
+                }
+                _ => return Err(Error::parse(n + 1, format!("invalid answer: {:?}", line))),
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Outcome, PartResult};
+    use std::time::Duration;
+
+    fn day_result(answers: &[i64]) -> DayResult {
+        DayResult {
+            day: 3,
+            parse_duration: Duration::default(),
+            parts: answers
+                .iter()
+                .enumerate()
+                .map(|(i, a)| PartResult {
+                    part: i as u8 + 1,
+                    outcome: Outcome::Answer(Answer::Number((*a).into())),
+                    duration: Duration::default(),
+                })
+                .collect(),
+            phases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        let answers: Answers = "# day part answer\n\n3 1 7\n3 2 336\n".parse().unwrap();
+
+        assert_eq!(answers.get(3, 1), Some("7"));
+        assert_eq!(answers.get(3, 2), Some("336"));
+        assert_eq!(answers.get(4, 1), None);
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        let answers = "3 x 7".parse::<Answers>();
+
+        assert!(answers.is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = "3 1 7\n3 2 336".parse().unwrap();
+
+        assert_eq!(answers.check(&day_result(&[7, 336])), vec![]);
+        assert_eq!(
+            answers.check(&day_result(&[7, 335])),
+            vec![Mismatch {
+                day: 3,
+                part: 2,
+                expected: "336".to_string(),
+                actual: "335".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_unrecorded() {
+        let answers: Answers = "3 1 7".parse().unwrap();
+
+        assert_eq!(answers.check(&day_result(&[7, 335])), vec![]);
+    }
+}
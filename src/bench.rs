@@ -0,0 +1,158 @@
+use crate::input::Source;
+use crate::{fmt_dur, Day, Result};
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Summary of the durations of repeated runs.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarize");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        // Nearest-rank percentile
+        let p95_rank = ((n as f64 * 0.95).ceil() as usize).max(1);
+
+        Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            p95: sorted[p95_rank - 1],
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {}, median {}, mean {} ± {}, p95 {}",
+            fmt_dur(self.min),
+            fmt_dur(self.median),
+            fmt_dur(self.mean),
+            fmt_dur(self.stddev),
+            fmt_dur(self.p95)
+        )
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+/// Number of untimed runs before measuring, a tenth of the timed runs.
+pub fn warmup_runs(runs: usize) -> usize {
+    (runs / 10).max(1)
+}
+
+fn measure<F: FnMut() -> Result<()>>(runs: usize, mut f: F) -> Result<Stats> {
+    for _ in 0..warmup_runs(runs) {
+        f()?;
+    }
+
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+
+    Ok(Stats::from_samples(&samples))
+}
+
+/// Runs parsing and each part of `day` `runs` times after a warm-up, and
+/// summarizes the durations of each stage.
+pub fn bench_day(day: &Day, source: &Source, part: Option<u8>, runs: usize) -> Result<BenchResult> {
+    let input = source.read(day.day)?;
+
+    let parse = measure(runs, || (day.parse)(&input).map(|p| drop(black_box(p))))
+        .map_err(|e| e.in_day(day.day))?;
+
+    let parsed = (day.parse)(&input).map_err(|e| e.in_day(day.day))?;
+    let mut parts = Vec::new();
+    for n in 1..=2 {
+        if part.is_some_and(|p| p != n) {
+            continue;
+        }
+
+        let stats = measure(runs, || parsed.solve(n).map(|a| drop(black_box(a))))
+            .map_err(|e| e.in_day(day.day))?;
+        parts.push((n, stats));
+    }
+
+    Ok(BenchResult {
+        day: day.day,
+        parse,
+        parts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn test_stats_odd() {
+        let stats = Stats::from_samples(&millis(&[5, 1, 3, 2, 4]));
+
+        assert_eq!(stats.runs, 5);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+        assert_eq!(stats.stddev.as_micros(), 1414);
+    }
+
+    #[test]
+    fn test_stats_even() {
+        let stats = Stats::from_samples(&millis(&[4, 1, 3, 2]));
+
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.p95, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn test_stats_single() {
+        let stats = Stats::from_samples(&millis(&[7]));
+
+        assert_eq!(stats.min, stats.p95);
+        assert_eq!(stats.stddev, Duration::default());
+    }
+
+    #[test]
+    fn test_warmup_runs() {
+        assert_eq!(warmup_runs(1), 1);
+        assert_eq!(warmup_runs(100), 10);
+    }
+}
@@ -0,0 +1,522 @@
+use advent_of_code::answers::{answers_path, Answers};
+use advent_of_code::bench::{bench_day, warmup_runs};
+use advent_of_code::day01::{self, Integer, TripletImpl};
+use advent_of_code::download::{fetch_default, Config, Fetched};
+use advent_of_code::duration::HumanDuration;
+use advent_of_code::history::{
+    compare as compare_runs, history_path, Environment, History, Record, DEFAULT_THRESHOLD,
+};
+use advent_of_code::input::Source;
+use advent_of_code::report::Format;
+use advent_of_code::scaffold::new_day;
+use advent_of_code::timer::PhaseTree;
+use advent_of_code::watch::watch as watch_day;
+use advent_of_code::{
+    fmt_dur, get_day, run_day, run_days, DayResult, Error, Outcome, Result, RunOptions, DAYS,
+};
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: aoc run <day|all> [<input>|-] [--part <1|2>]
+               [--format <text|table|json|csv>]
+               [--check | --bench <runs> [--name <name>]] [--timings]
+               [--deadline <duration>] [--max-total <duration>]
+       aoc watch <day> [<input>]
+       aoc sum [<input>|-] [--arity <k>] [--target <sum>]
+               [--type <i64|u64|i128>] [--impl <hash|sorted|bitset>]
+               [--all | --nearest | --nearest-below]
+       aoc compare [--baseline <name>] [--threshold <percent>]
+       aoc new <day>
+       aoc fetch <day|all> [--year <year>]
+
+All commands take --verbose, to log what the solvers found, or --explain,
+to also log how every line of the input was judged. Logs go to stderr.
+
+Inputs are read from $AOC_INPUT_DIR/dayNN.txt, or inputs/dayNN.txt in the
+crate root, unless a path or - (stdin) is given.
+
+With --check, answers are compared against the accepted answers in
+answers.txt next to the input directory. With --bench, parsing and each part
+are run the given number of times and summarized, and the median of each part
+is appended to bench_history.tsv next to the input directory, along with the
+commit, rustc version and CPU model. --name names the run as a baseline. With
+--timings, a tree of the time spent in each phase, including phases timed by
+the solver, is printed to stderr after the answers. When built with the
+alloc-stats feature, it also shows the allocations made in each phase.
+
+Each part that takes longer than its deadline, 15s unless the day sets
+another one or --deadline is given, is reported as TIMEOUT and left running.
+A part that fails is reported as ERROR with its message, and the run exits
+with an error once all answers are written.
+With --max-total, the run fails unless all parts finish and parsing and
+solving take less than the given time in total. Durations are written like
+1m 30s or 500ms.
+
+aoc run all runs the days in parallel and prints a table of all answers and
+times with a grand total, unless another format is given. The parts of a day
+whose input cannot be read or parsed are reported as ERROR.
+
+The json and csv formats emit one record per part with its answer and the
+parse and solve times in nanoseconds.
+
+aoc new creates src/dayNN.rs from a template and an empty inputs/dayNN.txt,
+and registers the day in src/lib.rs.
+
+aoc fetch downloads missing inputs from adventofcode.com, using the session
+token in $AOC_SESSION or aoc.conf in the crate root. Inputs that are already
+present are never fetched again, empty ones like those aoc new creates are.
+
+aoc watch runs a day and its examples again whenever the input or an example
+changes, showing how the answers and times differ from the previous run. The
+examples run under the same deadline as the day, and while a part that timed
+out is still running, the day is not run again.
+
+aoc sum finds <k> (default 2) expense report entries of day 1 that add up to
+<sum> (default 2020) and multiplies them. With --all, every combination of
+entries that adds up is listed with the lines they are on. Entries are read
+as --type integers, i64 by default; products that do not fit an i128 are
+reported as errors. --impl picks how three entries are searched for: hash
+copies and hashes the remaining entries for each entry, sorted closes in on
+the sum from both ends of the sorted entries, and bitset, the default, walks
+the values in bitsets when they span at most 2^24 values. With --nearest,
+when no entries add up, the entries whose sum is closest to <sum> are
+reported with how far off they are; --nearest-below only takes sums below
+<sum>.
+
+aoc compare compares the latest benchmark run against the one before it, or
+the latest run named <name>, and fails when a part got more than <percent>
+(default 10) slower.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn new(args: &[String]) -> Result<()> {
+    let day = match args {
+        [day] => day.parse::<u8>().ok().filter(|d| (1..=25).contains(d)),
+        _ => None,
+    }
+    .unwrap_or_else(|| usage());
+
+    for path in new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day)? {
+        println!("Created {}", path.display());
+    }
+    Ok(())
+}
+
+fn fetch(args: &[String]) -> Result<()> {
+    let target = args.first().unwrap_or_else(|| usage());
+    let mut config = Config::load()?;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--year" => match rest.next().and_then(|y| y.parse::<u16>().ok()) {
+                Some(year) => config.year = year,
+                None => usage(),
+            },
+            _ => usage(),
+        }
+    }
+
+    let days: Vec<u8> = if target == "all" {
+        DAYS.iter().map(|d| d.day).collect()
+    } else {
+        match target.parse::<u8>() {
+            Ok(day @ 1..=25) => vec![day],
+            _ => usage(),
+        }
+    };
+
+    for day in days {
+        match fetch_default(&config, day)? {
+            Fetched::Cached(path) => println!("Cached {}", path.display()),
+            Fetched::Downloaded(path) => println!("Downloaded {}", path.display()),
+        }
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let target = args.first().unwrap_or_else(|| usage());
+    let mut part = None;
+    let mut source = Source::Default;
+    let mut check = false;
+    let mut timings = false;
+    let mut bench = None;
+    let mut name = None;
+    let mut format = None;
+    let mut deadline = None;
+    let mut max_total = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--part" => match rest.next().and_then(|p| p.parse::<u8>().ok()) {
+                Some(p @ 1..=2) => part = Some(p),
+                _ => usage(),
+            },
+            "--check" => check = true,
+            "--timings" => timings = true,
+            "--format" => match rest.next().and_then(|f| f.parse::<Format>().ok()) {
+                Some(f) => format = Some(f),
+                None => usage(),
+            },
+            "--deadline" => match rest.next().and_then(|d| d.parse::<HumanDuration>().ok()) {
+                Some(d) => deadline = Some(d.into()),
+                None => usage(),
+            },
+            "--max-total" => match rest.next().and_then(|d| d.parse::<HumanDuration>().ok()) {
+                Some(d) => max_total = Some(d.into()),
+                None => usage(),
+            },
+            "--name" => match rest.next() {
+                Some(n) => name = Some(n.as_str()),
+                None => usage(),
+            },
+            "--bench" => match rest.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => bench = Some(n),
+                _ => usage(),
+            },
+            input if source == Source::Default && (input == "-" || !input.starts_with('-')) => {
+                source = Source::from_arg(input)
+            }
+            _ => usage(),
+        }
+    }
+
+    let bench_format = format.is_none_or(|f| f == Format::Text);
+    if (bench.is_some() && (check || !bench_format)) || (name.is_some() && bench.is_none()) {
+        usage();
+    }
+
+    let days = if target == "all" {
+        if source != Source::Default {
+            return Err("an input can only be given when running a single day".into());
+        }
+        DAYS.iter().collect()
+    } else {
+        let day = target
+            .parse::<u8>()
+            .ok()
+            .and_then(get_day)
+            .ok_or_else(|| format!("no solver registered for day {}", target))?;
+        vec![day]
+    };
+
+    if let Some(runs) = bench {
+        let mut results = Vec::new();
+        for day in days {
+            let result = bench_day(day, &source, part, runs)?;
+            println!("Day {:02}", day.day);
+            println!("{} runs, {} warm-up", runs, warmup_runs(runs));
+            println!("Parse:  {}", result.parse);
+            for (n, stats) in result.parts.iter() {
+                println!("Part {}: {}", n, stats);
+            }
+            results.push(result);
+        }
+        let records = Record::from_bench(&results, name, &Environment::detect());
+        History::append(&history_path(), &records)?;
+        eprintln!("Recorded in {}", history_path().display());
+        return Ok(());
+    }
+
+    let answers = if check {
+        Some(Answers::load(&answers_path())?)
+    } else {
+        None
+    };
+
+    let options = RunOptions { part, deadline };
+    let results = if target == "all" {
+        run_days(&days, &source, options)
+            .into_iter()
+            .zip(days.iter())
+            .map(|(result, day)| {
+                result.unwrap_or_else(|err| DayResult::failed(day.day, part, &err))
+            })
+            .collect()
+    } else {
+        vec![run_day(days[0], &source, options)?]
+    };
+    let format = format.unwrap_or(if target == "all" {
+        Format::Table
+    } else {
+        Format::Text
+    });
+    format.write(&mut io::stdout().lock(), &results)?;
+
+    if timings {
+        for result in results.iter() {
+            eprintln!("\nDay {:02} timings", result.day);
+            eprint!("{}", PhaseTree(&result.phases));
+        }
+    }
+
+    let checks = vec![
+        answers.map_or(Ok(()), |answers| check_answers(&answers, &results)),
+        max_total.map_or(Ok(()), |max_total| check_total(max_total, &results)),
+        check_failures(&results),
+    ];
+    checks.into_iter().collect()
+}
+
+fn check_failures(results: &[DayResult]) -> Result<()> {
+    let failures = results
+        .iter()
+        .flat_map(|r| r.parts.iter())
+        .filter(|p| matches!(p.outcome, Outcome::Error(_)))
+        .count();
+    if failures > 0 {
+        return Err(format!("{} part(s) failed", failures).into());
+    }
+    Ok(())
+}
+
+fn check_total(max_total: Duration, results: &[DayResult]) -> Result<()> {
+    let timeouts = results
+        .iter()
+        .flat_map(|r| r.parts.iter())
+        .filter(|p| p.outcome == Outcome::Timeout)
+        .count();
+    if timeouts > 0 {
+        return Err(format!("{} part(s) timed out", timeouts).into());
+    }
+
+    let total: Duration = results.iter().map(DayResult::total_duration).sum();
+    if total > max_total {
+        return Err(format!(
+            "took {} in total, more than {}",
+            fmt_dur(total),
+            fmt_dur(max_total)
+        )
+        .into());
+    }
+    eprintln!(
+        "Took {} in total, within {}",
+        fmt_dur(total),
+        fmt_dur(max_total)
+    );
+    Ok(())
+}
+
+fn watch(args: &[String]) -> Result<()> {
+    let (target, source) = match args {
+        [day] => (day, Source::Default),
+        [day, input] => (day, Source::from_arg(input)),
+        _ => usage(),
+    };
+    let day = target
+        .parse::<u8>()
+        .ok()
+        .and_then(get_day)
+        .ok_or_else(|| format!("no solver registered for day {}", target))?;
+
+    watch_day(day, &source)
+}
+
+/// How `aoc sum` searches the entries.
+struct SumOptions {
+    arity: usize,
+    imp: TripletImpl,
+    all: bool,
+    /// Whether to report the nearest sum when no entries add up, and if so
+    /// whether it has to be below the target.
+    nearest: Option<bool>,
+}
+
+fn sum(args: &[String]) -> Result<()> {
+    let mut source = Source::Default;
+    let mut target = day01::TARGET.to_string();
+    let mut integer = "i64";
+    let mut options = SumOptions {
+        arity: 2,
+        imp: TripletImpl::default(),
+        all: false,
+        nearest: None,
+    };
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--arity" => match rest.next().and_then(|k| k.parse::<usize>().ok()) {
+                Some(k) => options.arity = k,
+                None => usage(),
+            },
+            "--target" => match rest.next() {
+                Some(t) => target = t.clone(),
+                None => usage(),
+            },
+            "--type" => match rest.next().map(String::as_str) {
+                Some(t @ ("i64" | "u64" | "i128")) => integer = t,
+                _ => usage(),
+            },
+            "--impl" => match rest.next().and_then(|i| i.parse::<TripletImpl>().ok()) {
+                Some(i) => options.imp = i,
+                None => usage(),
+            },
+            "--all" => options.all = true,
+            "--nearest" => options.nearest = Some(false),
+            "--nearest-below" => options.nearest = Some(true),
+            input if source == Source::Default && (input == "-" || !input.starts_with('-')) => {
+                source = Source::from_arg(input)
+            }
+            _ => usage(),
+        }
+    }
+
+    if options.all && options.nearest.is_some() {
+        usage();
+    }
+
+    let input = source.read(1)?;
+    match integer {
+        "u64" => sum_entries::<u64>(&input, &target, &options),
+        "i128" => sum_entries::<i128>(&input, &target, &options),
+        _ => sum_entries::<i64>(&input, &target, &options),
+    }
+}
+
+fn sum_entries<T: Integer>(input: &str, target: &str, options: &SumOptions) -> Result<()> {
+    let entries = day01::parse::<T>(input)?;
+    let target: T = target
+        .parse()
+        .map_err(|e| format!("invalid target {:?}: {}", target, e))?;
+    if options.all {
+        let combinations = day01::find_all_combinations(&entries, options.arity, target);
+        for combination in combinations.iter() {
+            let product = day01::product(&combination.values)?;
+            println!("{} = {}, product {}", combination, target, product);
+        }
+        println!("{} combination(s) found", combinations.len());
+        return Ok(());
+    }
+
+    let (found, product) = match (
+        day01::solve_with(&entries, options.arity, target, options.imp),
+        options.nearest,
+    ) {
+        (Err(Error::NoSolution), Some(below)) => {
+            let nearest = day01::find_nearest(&entries, options.arity, target, below)
+                .ok_or(Error::NoSolution)?;
+            println!("No exact match, nearest: {}", nearest);
+            println!("Product: {}", day01::product(&nearest.combination.values)?);
+            return Ok(());
+        }
+        (solved, _) => solved?,
+    };
+    let terms: Vec<String> = found.iter().map(T::to_string).collect();
+    println!("{} = {}", terms.join(" + "), target);
+    println!("Product: {}", product);
+    Ok(())
+}
+
+fn compare(args: &[String]) -> Result<()> {
+    let mut baseline = None;
+    let mut threshold = DEFAULT_THRESHOLD;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--baseline" => match rest.next() {
+                Some(name) => baseline = Some(name.as_str()),
+                None => usage(),
+            },
+            "--threshold" => match rest.next().and_then(|t| t.parse::<f64>().ok()) {
+                Some(t) if t >= 0.0 => threshold = t,
+                _ => usage(),
+            },
+            _ => usage(),
+        }
+    }
+
+    let history = History::load(&history_path())?;
+    let (before, after) = history.latest_pair(baseline)?;
+    if before[0].env.cpu != after[0].env.cpu {
+        eprintln!("Warning: the runs were recorded on different CPUs");
+    }
+
+    let changes = compare_runs(&before, &after);
+    let mut slower = 0;
+    for change in changes.iter() {
+        if change.is_regression(threshold) {
+            slower += 1;
+            println!("{}  SLOWER", change);
+        } else {
+            println!("{}", change);
+        }
+    }
+
+    if slower > 0 {
+        return Err(format!("{} part(s) got more than {}% slower", slower, threshold).into());
+    }
+    Ok(())
+}
+
+fn check_answers(answers: &Answers, results: &[DayResult]) -> Result<()> {
+    let mismatches: Vec<_> = results.iter().flat_map(|r| answers.check(r)).collect();
+    if mismatches.is_empty() {
+        eprintln!("All answers match {}", answers_path().display());
+        return Ok(());
+    }
+
+    eprintln!();
+    for mismatch in mismatches.iter() {
+        eprintln!("{}", mismatch);
+    }
+    Err(format!(
+        "{} answer(s) differ from the accepted ones",
+        mismatches.len()
+    )
+    .into())
+}
+
+/// Writes log records to stderr, prefixed with the module they come from.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let target = record.target();
+        let module = target.rsplit("::").next().unwrap_or(target);
+        eprintln!("[{}] {}", module, record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let level = if args.iter().any(|a| a == "--explain") {
+        Some(log::LevelFilter::Trace)
+    } else if args.iter().any(|a| a == "--verbose") {
+        Some(log::LevelFilter::Debug)
+    } else {
+        None
+    };
+    args.retain(|a| a != "--explain" && a != "--verbose");
+    if let Some(level) = level {
+        log::set_logger(&LOGGER).expect("no other logger is set");
+        log::set_max_level(level);
+    }
+
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("new") => new(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("watch") => watch(&args[1..]),
+        Some("sum") => sum(&args[1..]),
+        Some("compare") => compare(&args[1..]),
+        _ => usage(),
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
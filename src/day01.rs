@@ -1,31 +1,75 @@
-use advent_of_code::fmt_dur;
+use crate::{Answer, Error, Result, Solver};
+use log::debug;
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::env;
-use std::fs;
-use std::time::Instant;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::str::FromStr;
 
-fn find_pair(input: Vec<i32>, n: usize, k: &i32) -> Option<(i32, i32)> {
-    let mut map: HashMap<i32, bool> = HashMap::with_capacity(n);
+/// Integer types expense entries can be read as, anything fitting an `i128`.
+///
+/// Entries are summed and multiplied as `i128`s with checked arithmetic.
+/// Sums can only overflow with `i128` entries close to its limits, and
+/// combinations whose sum overflows are never found.
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Into<i128>
+    + TryFrom<i128>
+    + FromStr<Err = ParseIntError>
+    + fmt::Display
+    + fmt::Debug
+{
+}
+
+impl<T> Integer for T where
+    T: Copy
+        + Ord
+        + Hash
+        + Into<i128>
+        + TryFrom<i128>
+        + FromStr<Err = ParseIntError>
+        + fmt::Display
+        + fmt::Debug
+{
+}
+
+/// Finds two entries in `input` that sum up to `k`.
+pub fn find_pair<T: Integer>(input: &[T], k: T) -> Option<(T, T)> {
+    pair_summing_to(input, k.into())
+}
+
+fn pair_summing_to<T: Integer>(input: &[T], k: i128) -> Option<(T, T)> {
+    let mut map: HashMap<i128, T> = HashMap::with_capacity(input.len());
 
     for i in input.iter() {
-        let x = k - i;
-        if map.contains_key(&x) {
-            return Some((x, *i));
+        let x = k.checked_sub((*i).into());
+        if let Some(x) = x.and_then(|x| map.get(&x)) {
+            return Some((*x, *i));
         } else {
-            map.insert(*i, true);
+            map.insert((*i).into(), *i);
         }
     }
 
     None
 }
 
-fn find_triplet(input: Vec<i32>, k: &i32) -> Option<(i32, i32, i32)> {
+/// Finds three entries in `input` that sum up to `k`.
+///
+/// Copies the rest of the input and hashes it for every entry it tries, see
+/// `TripletImpl` for faster searches.
+pub fn find_triplet<T: Integer>(input: &[T], k: T) -> Option<(T, T, T)> {
     for (pos, a) in input.iter().enumerate() {
-        let new_k = k - a;
+        let new_k = match k.into().checked_sub((*a).into()) {
+            Some(new_k) => new_k,
+            None => continue,
+        };
         let mut new_vec = vec![];
         new_vec.extend_from_slice(&input[pos + 1..]);
-        let n = new_vec.len();
-        if let Some((b, c)) = find_pair(new_vec, n, &new_k) {
+        if let Some((b, c)) = pair_summing_to(&new_vec, new_k) {
             return Some((*a, b, c));
         }
     }
@@ -33,39 +77,604 @@ fn find_triplet(input: Vec<i32>, k: &i32) -> Option<(i32, i32, i32)> {
     None
 }
 
-fn part1(lines: Vec<i32>, n: usize, k: i32) {
-    match find_pair(lines, n, &k) {
-        Some((a, b)) => println!("{} * {} = {}", a, b, a * b),
-        None => println!("Did not find a matching pair"),
+/// The sum of `values`, `None` when it overflows.
+fn sum<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<i128> {
+    values
+        .into_iter()
+        .try_fold(0i128, |acc, x| acc.checked_add(x.into()))
+}
+
+/// Compares `a + b` to `target`. A sum that overflows is beyond every `i128`
+/// in the direction of `b`, the larger of the two when they are sorted.
+fn cmp_pair(a: i128, b: i128, target: i128) -> Ordering {
+    match a.checked_add(b) {
+        Some(sum) => sum.cmp(&target),
+        None if b > 0 => Ordering::Greater,
+        None => Ordering::Less,
+    }
+}
+
+/// Finds `k` entries at distinct positions in `input` that sum up to
+/// `target`, returned in ascending order.
+///
+/// Pairs are found with a hash set in one pass, triplets with the default
+/// `TripletImpl`, and larger combinations by meeting in the middle: the sums
+/// of all combinations of the latter half are stored, then looked up for
+/// each combination of the first half.
+pub fn find_combination<T: Integer>(input: &[T], k: usize, target: T) -> Option<Vec<T>> {
+    find_combination_with(input, k, target, TripletImpl::default())
+}
+
+/// `find_combination`, searching for triplets with `imp`.
+pub fn find_combination_with<T: Integer>(
+    input: &[T],
+    k: usize,
+    target: T,
+    imp: TripletImpl,
+) -> Option<Vec<T>> {
+    let mut found = match k {
+        _ if k > input.len() => None,
+        0 => Some(vec![]).filter(|_| target.into() == 0),
+        1 => input.iter().find(|x| **x == target).map(|x| vec![*x]),
+        2 => find_pair(input, target).map(|(a, b)| vec![a, b]),
+        3 => find_triplet_with(input, target, imp).map(|(a, b, c)| vec![a, b, c]),
+        _ => meet_in_the_middle(input, k, target.into()),
+    }?;
+    found.sort_unstable();
+    Some(found)
+}
+
+/// How `find_triplet_with` searches for three entries summing up to a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TripletImpl {
+    /// `find_triplet`, looking up pairs in a hash map of the remaining input
+    /// for every entry.
+    Hash,
+    /// Sorts a copy of the input, then closes in on the target from both
+    /// ends for every entry.
+    Sorted,
+    /// Marks the entries in bitsets over their range of values and walks the
+    /// values in ascending order. Falls back to `Sorted` when the range is
+    /// larger than `BITSET_MAX_RANGE`.
+    #[default]
+    Bitset,
+}
+
+impl FromStr for TripletImpl {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "hash" => Ok(TripletImpl::Hash),
+            "sorted" => Ok(TripletImpl::Sorted),
+            "bitset" => Ok(TripletImpl::Bitset),
+            _ => Err(format!("unknown triplet search: {}", s)),
+        }
+    }
+}
+
+/// The largest range of values `TripletImpl::Bitset` is used for, taking
+/// three bitsets of 2 MiB.
+pub const BITSET_MAX_RANGE: i128 = 1 << 24;
+
+/// Finds three entries at distinct positions in `input` that sum up to `k`,
+/// searching with `imp`. Apart from `Hash`, the entries are returned in
+/// ascending order.
+pub fn find_triplet_with<T: Integer>(input: &[T], k: T, imp: TripletImpl) -> Option<(T, T, T)> {
+    match imp {
+        TripletImpl::Hash => find_triplet(input, k),
+        TripletImpl::Sorted => find_sorted_triplet(input, k),
+        TripletImpl::Bitset => find_bitset_triplet(input, k),
+    }
+}
+
+fn find_sorted_triplet<T: Integer>(input: &[T], target: T) -> Option<(T, T, T)> {
+    let mut sorted = input.to_vec();
+    sorted.sort_unstable();
+
+    for (i, a) in sorted.iter().enumerate() {
+        // The pairs after an entry equal to `a` include all pairs after `a`.
+        if i > 0 && sorted[i - 1] == *a {
+            continue;
+        }
+        let rest = match target.into().checked_sub((*a).into()) {
+            Some(rest) => rest,
+            None => continue,
+        };
+        let lo = i + 1;
+        if lo + 1 >= sorted.len() {
+            break;
+        }
+        // The entries after `a` only get larger, and so do the pairs of them.
+        if cmp_pair(sorted[lo].into(), sorted[lo + 1].into(), rest) == Ordering::Greater {
+            break;
+        }
+
+        // Nothing above the largest entry that fits with the smallest one.
+        let fits = sorted[lo + 1..].partition_point(|x| {
+            cmp_pair(sorted[lo].into(), (*x).into(), rest) != Ordering::Greater
+        });
+        let (mut lo, mut hi) = (lo, lo + fits);
+        while lo < hi {
+            match cmp_pair(sorted[lo].into(), sorted[hi].into(), rest) {
+                Ordering::Less => lo += 1,
+                Ordering::Greater => hi -= 1,
+                Ordering::Equal => return Some((*a, sorted[lo], sorted[hi])),
+            }
+        }
+    }
+
+    None
+}
+
+/// A set of offsets into a range of values.
+struct Bitset(Vec<u64>);
+
+impl Bitset {
+    fn new(len: usize) -> Bitset {
+        Bitset(vec![0; len.div_ceil(64)])
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.0.get(i / 64).is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+
+    /// The smallest offset in the set from `from` on.
+    fn next(&self, from: usize) -> Option<usize> {
+        let mut word = from / 64;
+        let mut bits = *self.0.get(word)? & (!0 << (from % 64));
+        while bits == 0 {
+            word += 1;
+            bits = *self.0.get(word)?;
+        }
+        Some(word * 64 + bits.trailing_zeros() as usize)
+    }
+}
+
+/// Walks the distinct values `a <= b <= c`, with `c` what is left of the
+/// target, moving on to the next `a` once `c` drops below `b`. `counts[n]`
+/// holds the values occurring more than `n` times, so a value can be picked
+/// as often as it occurs.
+fn find_bitset_triplet<T: Integer>(input: &[T], target: T) -> Option<(T, T, T)> {
+    let min: i128 = (*input.iter().min()?).into();
+    let max: i128 = (*input.iter().max()?).into();
+    if max.checked_sub(min).is_none_or(|r| r >= BITSET_MAX_RANGE) {
+        debug!(
+            "entries from {} to {} span too many values for bitsets",
+            min, max
+        );
+        return find_sorted_triplet(input, target);
+    }
+
+    let len = (max - min + 1) as usize;
+    let mut counts = [Bitset::new(len), Bitset::new(len), Bitset::new(len)];
+    for x in input.iter() {
+        let i = ((*x).into() - min) as usize;
+        if let Some(count) = counts.iter_mut().find(|c| !c.contains(i)) {
+            count.insert(i);
+        }
+    }
+
+    let offset = |v: i128| (v - min) as usize;
+    let has = |values: [i128; 3]| {
+        values.iter().all(|v| {
+            let times = values.iter().filter(|w| *w == v).count();
+            counts[times - 1].contains(offset(*v))
+        })
+    };
+
+    let target = target.into();
+    let mut a = counts[0].next(0);
+    while let Some(ai) = a {
+        let av = min + ai as i128;
+        let mut b = counts[0].next(ai);
+        while let Some(bi) = b {
+            let bv = min + bi as i128;
+            let cv = match target.checked_sub(av).and_then(|r| r.checked_sub(bv)) {
+                Some(cv) => cv,
+                None if bv > 0 => break,
+                None => {
+                    b = counts[0].next(bi + 1);
+                    continue;
+                }
+            };
+            if cv < bv {
+                break;
+            }
+            if cv <= max && has([av, bv, cv]) {
+                let value = |v: i128| T::try_from(v).ok();
+                return Some((value(av)?, value(bv)?, value(cv)?));
+            }
+            b = counts[0].next(bi + 1);
+        }
+        a = counts[0].next(ai + 1);
+    }
+
+    None
+}
+
+/// Calls `f` with the indices of every `k`-combination of `0..n`, in
+/// lexicographic order.
+fn for_each_combination<F: FnMut(&[usize]) -> bool>(n: usize, k: usize, mut f: F) {
+    if k > n {
+        return;
+    }
+    let mut indices: Vec<usize> = (0..k).collect();
+    loop {
+        if !f(&indices) {
+            return;
+        }
+        let i = match (0..k).rev().find(|i| indices[*i] < n - k + i) {
+            Some(i) => i,
+            None => return,
+        };
+        indices[i] += 1;
+        for j in i + 1..k {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+/// Splits every solution into its `k / 2` first and remaining positions. Of
+/// the latter combinations with the same sum, only the one starting last is
+/// kept: a first half ending before any of them also ends before that one.
+fn meet_in_the_middle<T: Integer>(input: &[T], k: usize, target: i128) -> Option<Vec<T>> {
+    let (first, second) = (k / 2, k - k / 2);
+    let sum_of = |indices: &[usize]| sum(indices.iter().map(|i| input[*i]));
+
+    let mut latter: HashMap<i128, Vec<usize>> = HashMap::new();
+    for_each_combination(input.len(), second, |indices| {
+        let sum = match sum_of(indices) {
+            Some(sum) => sum,
+            None => return true,
+        };
+        let kept = latter.entry(sum).or_insert_with(|| indices.to_vec());
+        if kept[0] < indices[0] {
+            *kept = indices.to_vec();
+        }
+        true
+    });
+
+    let mut found = None;
+    for_each_combination(input.len(), first, |indices| {
+        let last = indices[first - 1];
+        let rest = sum_of(indices).and_then(|sum| target.checked_sub(sum));
+        match rest.and_then(|rest| latter.get(&rest)) {
+            Some(rest) if rest[0] > last => {
+                let all = indices.iter().chain(rest.iter());
+                found = Some(all.map(|i| input[*i]).collect());
+                false
+            }
+            _ => true,
+        }
+    });
+    found
+}
+
+/// Entries of the input that add up to the target, with their 0-based
+/// positions in the input, in ascending order of position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combination<T> {
+    pub indices: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+impl<T: fmt::Display> fmt::Display for Combination<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<String> = self
+            .indices
+            .iter()
+            .zip(self.values.iter())
+            .map(|(i, v)| format!("{} (line {})", v, i + 1))
+            .collect();
+        f.write_str(&terms.join(" + "))
+    }
+}
+
+/// Finds every set of `k` positions in `input` whose entries sum up to
+/// `target`, ordered by position.
+///
+/// Equal entries at different positions are different solutions, so two
+/// entries of 1010 make one pair summing to 2020, and three make three.
+pub fn find_all_combinations<T: Integer>(input: &[T], k: usize, target: T) -> Vec<Combination<T>> {
+    let mut sorted: Vec<(i128, usize)> = input
+        .iter()
+        .enumerate()
+        .map(|(i, x)| ((*x).into(), i))
+        .collect();
+    sorted.sort_unstable();
+
+    let mut found = Vec::new();
+    collect_combinations(&sorted, k, target.into(), &mut Vec::new(), &mut found);
+
+    let mut combinations: Vec<Combination<T>> = found
+        .into_iter()
+        .map(|mut indices| {
+            indices.sort_unstable();
+            let values = indices.iter().map(|i| input[*i]).collect();
+            Combination { indices, values }
+        })
+        .collect();
+    combinations.sort_unstable_by(|a, b| a.indices.cmp(&b.indices));
+    combinations
+}
+
+/// For every `k` entries of `sorted` summing up to `target`, pushes their
+/// positions along with `chosen` to `found`. `sorted` holds the entries with
+/// their positions, ordered by value.
+fn collect_combinations(
+    sorted: &[(i128, usize)],
+    k: usize,
+    target: i128,
+    chosen: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    let with = |chosen: &Vec<usize>, more: &[usize]| {
+        let mut all = chosen.clone();
+        all.extend_from_slice(more);
+        all
+    };
+
+    match k {
+        0 if target == 0 => found.push(chosen.clone()),
+        0 => {}
+        1 => {
+            for (x, i) in sorted.iter() {
+                if *x == target {
+                    found.push(with(chosen, &[*i]));
+                }
+            }
+        }
+        2 => {
+            let (mut lo, mut hi) = (0, sorted.len());
+            while lo + 1 < hi {
+                match cmp_pair(sorted[lo].0, sorted[hi - 1].0, target) {
+                    Ordering::Less => lo += 1,
+                    Ordering::Greater => hi -= 1,
+                    Ordering::Equal if sorted[lo].0 == sorted[hi - 1].0 => {
+                        // Every pair within the run of equal entries matches.
+                        for a in lo..hi {
+                            for b in a + 1..hi {
+                                found.push(with(chosen, &[sorted[a].1, sorted[b].1]));
+                            }
+                        }
+                        return;
+                    }
+                    Ordering::Equal => {
+                        let low_end = lo
+                            + sorted[lo..]
+                                .iter()
+                                .take_while(|e| e.0 == sorted[lo].0)
+                                .count();
+                        let high_start = hi
+                            - sorted[..hi]
+                                .iter()
+                                .rev()
+                                .take_while(|e| e.0 == sorted[hi - 1].0)
+                                .count();
+                        for a in lo..low_end {
+                            for b in high_start..hi {
+                                found.push(with(chosen, &[sorted[a].1, sorted[b].1]));
+                            }
+                        }
+                        lo = low_end;
+                        hi = high_start;
+                    }
+                }
+            }
+        }
+        _ => {
+            for (pos, (x, i)) in sorted.iter().enumerate() {
+                let rest = match target.checked_sub(*x) {
+                    Some(rest) => rest,
+                    None => continue,
+                };
+                chosen.push(*i);
+                collect_combinations(&sorted[pos + 1..], k - 1, rest, chosen, found);
+                chosen.pop();
+            }
+        }
+    }
+}
+
+/// The entries whose sum comes closest to a target, see `find_nearest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nearest<T> {
+    pub combination: Combination<T>,
+    pub sum: i128,
+    pub target: i128,
+}
+
+impl<T> Nearest<T> {
+    /// How far the sum is above the target, negative when it is below.
+    pub fn gap(&self) -> i128 {
+        self.sum - self.target
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Nearest<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.combination, self.sum)?;
+        match self.gap() {
+            0 => Ok(()),
+            gap if gap < 0 => write!(f, ", {} below {}", -gap, self.target),
+            gap => write!(f, ", {} above {}", gap, self.target),
+        }
+    }
+}
+
+/// Finds the `k` entries at distinct positions in `input` whose sum is
+/// closest to `target`, or closest from below with `below`. Of sums equally
+/// far away, the one below the target is taken.
+///
+/// Exact matches are found as well, with a gap of 0. Every entry is tried
+/// with the nearest combinations of the larger entries, so this takes
+/// `O(n^(k - 1))` time for `k` of 2 and above.
+pub fn find_nearest<T: Integer>(
+    input: &[T],
+    k: usize,
+    target: T,
+    below: bool,
+) -> Option<Nearest<T>> {
+    let mut sorted: Vec<(i128, usize)> = input
+        .iter()
+        .enumerate()
+        .map(|(i, x)| ((*x).into(), i))
+        .collect();
+    sorted.sort_unstable();
+
+    let target = target.into();
+    let mut best = None;
+    nearest_combination(&sorted, k, target, below, &mut Vec::new(), &mut best);
+
+    let (gap, mut indices) = best?;
+    indices.sort_unstable();
+    let values = indices.iter().map(|i| input[*i]).collect();
+    Some(Nearest {
+        combination: Combination { indices, values },
+        sum: target.checked_add(gap)?,
+        target,
+    })
+}
+
+/// Replaces `best` with `chosen` and `more` when their sum is `gap` away from
+/// the target and `gap` is better. Returns whether the target is hit.
+fn offer(
+    best: &mut Option<(i128, Vec<usize>)>,
+    gap: i128,
+    below: bool,
+    chosen: &[usize],
+    more: &[usize],
+) -> bool {
+    let key = |gap: i128| (gap.unsigned_abs(), gap > 0);
+    if below && gap > 0 {
+        return false;
     }
+    if best.as_ref().is_none_or(|(b, _)| key(gap) < key(*b)) {
+        *best = Some((gap, [chosen, more].concat()));
+    }
+    gap == 0
 }
 
-fn part2(lines: Vec<i32>, k: i32) {
-    match find_triplet(lines, &k) {
-        Some((a, b, c)) => println!("{} * {} * {} = {}", a, b, c, a * b * c),
-        None => println!("Did not find a matching triplet"),
+/// Like `collect_combinations`, but keeps the combination nearest to
+/// `target` in `best`, as its distance from the target and its positions.
+/// Returns once the target is hit.
+fn nearest_combination(
+    sorted: &[(i128, usize)],
+    k: usize,
+    target: i128,
+    below: bool,
+    chosen: &mut Vec<usize>,
+    best: &mut Option<(i128, Vec<usize>)>,
+) -> bool {
+    match k {
+        0 => match target.checked_neg() {
+            Some(gap) => offer(best, gap, below, chosen, &[]),
+            None => false,
+        },
+        1 => sorted.iter().any(|(x, i)| match x.checked_sub(target) {
+            Some(gap) => offer(best, gap, below, chosen, &[*i]),
+            None => false,
+        }),
+        2 => {
+            let (mut lo, mut hi) = (0, sorted.len());
+            while lo + 1 < hi {
+                let (a, b) = (sorted[lo], sorted[hi - 1]);
+                let gap = a.0.checked_add(b.0).and_then(|sum| sum.checked_sub(target));
+                if let Some(gap) = gap {
+                    if offer(best, gap, below, chosen, &[a.1, b.1]) {
+                        return true;
+                    }
+                }
+                match cmp_pair(a.0, b.0, target) {
+                    Ordering::Less => lo += 1,
+                    _ => hi -= 1,
+                }
+            }
+            false
+        }
+        _ => {
+            for (pos, (x, i)) in sorted.iter().enumerate() {
+                let rest = match target.checked_sub(*x) {
+                    Some(rest) => rest,
+                    None => continue,
+                };
+                chosen.push(*i);
+                let hit = nearest_combination(&sorted[pos + 1..], k - 1, rest, below, chosen, best);
+                chosen.pop();
+                if hit {
+                    return true;
+                }
+            }
+            false
+        }
     }
 }
 
-fn main() {
-    // Read input file
-    let cwd = env::current_dir().unwrap();
-    let filename = cwd.join("inputs/day01.txt");
-    println!("Reading {}", filename.display());
-    let input = fs::read_to_string(filename).expect("Error while reading");
-    let lines: Vec<i32> = input.lines().map(|s| s.parse::<i32>().unwrap()).collect();
+/// The sum the entries of both parts have to add up to.
+pub const TARGET: i64 = 2020;
 
-    println!("Running Part 1");
-    let part1_start = Instant::now();
-    part1(lines.clone(), lines.len(), 2020);
-    let part1_dur = part1_start.elapsed();
-    println!("Took {}", fmt_dur(part1_dur));
+/// Finds `k` entries summing up to `target` and multiplies them.
+pub fn solve<T: Integer>(input: &[T], k: usize, target: T) -> Result<(Vec<T>, i128)> {
+    solve_with(input, k, target, TripletImpl::default())
+}
+
+/// `solve`, searching for triplets with `imp`.
+pub fn solve_with<T: Integer>(
+    input: &[T],
+    k: usize,
+    target: T,
+    imp: TripletImpl,
+) -> Result<(Vec<T>, i128)> {
+    let found = find_combination_with(input, k, target, imp).ok_or(Error::NoSolution)?;
+    let terms: Vec<String> = found.iter().map(T::to_string).collect();
+    debug!("{} = {}", terms.join(" + "), target);
+
+    let product = product(&found)?;
+    Ok((found, product))
+}
 
-    println!("Running Part 2");
-    let part2_start = Instant::now();
-    part2(lines.clone(), 2020);
-    let part2_dur = part2_start.elapsed();
-    println!("Took {}", fmt_dur(part2_dur));
+/// Multiplies `values`, failing when the product does not fit an `i128`.
+pub fn product<T: Integer>(values: &[T]) -> Result<i128> {
+    values
+        .iter()
+        .try_fold(1i128, |acc, x| acc.checked_mul((*x).into()))
+        .ok_or_else(|| Error::Overflow(format!("the product of {:?}", values)))
+}
+
+/// Reads one entry per line.
+pub fn parse<T: Integer>(input: &str) -> Result<Vec<T>> {
+    let mut lines = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let entry = line
+            .parse::<T>()
+            .map_err(|e| Error::parse(n + 1, format!("{:?}: {}", line, e)))?;
+        lines.push(entry);
+    }
+    Ok(lines)
+}
+
+/// Day 1: Report Repair.
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        parse(input)
+    }
+
+    fn part1(lines: &Vec<i64>) -> Result<Answer> {
+        Ok(solve(lines, 2, TARGET)?.1.into())
+    }
+
+    fn part2(lines: &Vec<i64>) -> Result<Answer> {
+        Ok(solve(lines, 3, TARGET)?.1.into())
+    }
 }
 
 #[cfg(test)]
@@ -77,19 +686,301 @@ mod tests {
         let k = 2020;
         let mut input = Vec::new();
         input.extend([1721, 979, 366, 299, 675, 1456].iter());
-        let len = input.len();
-        if let Some((a, b)) = find_pair(input, len, &k) {
+        if let Some((a, b)) = find_pair(&input, k) {
             assert_eq!(a * b, 514579);
         }
     }
 
+    #[test]
+    fn test_solver() {
+        let input = Day01::parse("1721\n979\n366\n299\n675\n1456").unwrap();
+
+        assert_eq!(Day01::part1(&input).unwrap(), Answer::Number(514579));
+        assert_eq!(Day01::part2(&input).unwrap(), Answer::Number(241861950));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = Day01::parse("1721\n97x\n").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line 2: \"97x\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let input = Day01::parse("1\n2\n3").unwrap();
+
+        assert!(matches!(Day01::part1(&input), Err(Error::NoSolution)));
+        assert!(matches!(Day01::part2(&input), Err(Error::NoSolution)));
+    }
+
     #[test]
     fn test_part2() {
         let k = 2020;
         let mut input = Vec::new();
         input.extend([1721, 979, 366, 299, 675, 1456].iter());
-        if let Some((a, b, c)) = find_triplet(input, &k) {
+        if let Some((a, b, c)) = find_triplet(&input, k) {
             assert_eq!(a * b * c, 241861950);
         }
     }
+
+    #[test]
+    fn test_find_combination() {
+        let input = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(find_combination(&input, 2, 2020), Some(vec![299, 1721]));
+        assert_eq!(find_combination(&input, 3, 2020), Some(vec![366, 675, 979]));
+        assert_eq!(
+            find_combination(&input, 4, 979 + 366 + 299 + 1456),
+            Some(vec![299, 366, 979, 1456])
+        );
+        assert_eq!(
+            find_combination(&input, 5, 1721 + 979 + 366 + 675 + 1456),
+            Some(vec![366, 675, 979, 1456, 1721])
+        );
+        assert_eq!(find_combination(&input, 1, 675), Some(vec![675]));
+        assert_eq!(find_combination(&input, 0, 0), Some(vec![]));
+        assert_eq!(find_combination(&input, 4, 2020), None);
+        assert_eq!(find_combination(&input, 7, 2020), None);
+    }
+
+    #[test]
+    fn test_find_combination_distinct_positions() {
+        assert_eq!(find_combination(&[1010, 5], 2, 2020), None);
+        assert_eq!(
+            find_combination(&[1010, 5, 1010], 2, 2020),
+            Some(vec![1010, 1010])
+        );
+        assert_eq!(
+            find_combination(&[500, 1, 1, 500], 4, 1002),
+            Some(vec![1, 1, 500, 500])
+        );
+        assert_eq!(find_combination(&[500, 1, 500], 4, 1002), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = [1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(solve(&input, 2, TARGET).unwrap(), (vec![299, 1721], 514579));
+        assert!(matches!(solve(&input, 4, TARGET), Err(Error::NoSolution)));
+        let huge = [i128::MAX / 2, 3, -(i128::MAX / 2)];
+        assert!(matches!(solve(&huge, 3, 3), Err(Error::Overflow(_))));
+    }
+
+    #[test]
+    fn test_integer_types() {
+        let unsigned: Vec<u64> = parse("5000\n1721\n979\n366\n299\n675\n1456").unwrap();
+        assert_eq!(
+            solve(&unsigned, 2, 2020).unwrap(),
+            (vec![299, 1721], 514579)
+        );
+        assert_eq!(find_triplet(&unsigned, 2020), Some((979, 366, 675)));
+
+        let big = [u64::MAX - 3, 5, 3];
+        assert_eq!(
+            solve(&big, 2, u64::MAX).unwrap(),
+            (vec![3, u64::MAX - 3], 3 * i128::from(u64::MAX - 3))
+        );
+        assert_eq!(find_combination(&big, 3, u64::MAX), None);
+        assert!(parse::<u64>("12\n-3").is_err());
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let input = [i128::MAX, i128::MAX, 3, 1, -2];
+
+        assert_eq!(find_combination(&input, 3, 2), Some(vec![-2, 1, 3]));
+        assert_eq!(find_combination(&input, 2, 4), Some(vec![1, 3]));
+        assert_eq!(find_combination(&input, 4, 2), None);
+        assert_eq!(
+            indices(&find_all_combinations(&input, 2, i128::MAX - 2)),
+            vec![vec![0, 4], vec![1, 4]]
+        );
+        assert_eq!(
+            indices(&find_all_combinations(&input, 2, 4)),
+            vec![vec![2, 3]]
+        );
+        assert_eq!(find_all_combinations(&input, 3, 2).len(), 1);
+    }
+
+    #[test]
+    fn test_triplet_impls() {
+        let inputs: [&[i64]; 5] = [
+            &[1721, 979, 366, 299, 675, 1456],
+            &[4, 1, 3, 1, 2, 2, 4, 0, 3, -1],
+            &[5, 5, 5, -5, 10],
+            &[1 << 40, -(1 << 40), 7, 1, 2],
+            &[1, 2],
+        ];
+        let impls = [TripletImpl::Hash, TripletImpl::Sorted, TripletImpl::Bitset];
+
+        for input in inputs.iter() {
+            for target in -16..=2020 {
+                let mut expected = false;
+                for_each_combination(input.len(), 3, |indices| {
+                    expected = indices.iter().map(|i| input[*i]).sum::<i64>() == target;
+                    !expected
+                });
+
+                for imp in impls.iter() {
+                    let found = find_triplet_with(input, target, *imp);
+                    assert_eq!(found.is_some(), expected, "{:?}, {:?}", input, imp);
+                    if let Some((a, b, c)) = found {
+                        assert_eq!(a + b + c, target);
+                    }
+                }
+            }
+        }
+        assert_eq!("sorted".parse(), Ok(TripletImpl::Sorted));
+        assert!("fast".parse::<TripletImpl>().is_err());
+    }
+
+    #[test]
+    fn test_bitset() {
+        let mut set = Bitset::new(200);
+        set.insert(3);
+        set.insert(64);
+        set.insert(199);
+
+        assert!(set.contains(64) && !set.contains(65) && !set.contains(500));
+        assert_eq!(set.next(0), Some(3));
+        assert_eq!(set.next(4), Some(64));
+        assert_eq!(set.next(65), Some(199));
+        assert_eq!(set.next(200), None);
+    }
+
+    #[test]
+    fn test_find_nearest() {
+        let input = [1721, 979, 366, 299, 675, 1456];
+
+        let exact = find_nearest(&input, 2, 2020, false).unwrap();
+        assert_eq!(
+            (exact.gap(), exact.to_string()),
+            (0, "1721 (line 1) + 299 (line 4) = 2020".to_string())
+        );
+
+        let above = find_nearest(&input, 2, 2019, false).unwrap();
+        assert_eq!(above.gap(), 1);
+        assert_eq!(
+            above.to_string(),
+            "1721 (line 1) + 299 (line 4) = 2020, 1 above 2019"
+        );
+
+        let below = find_nearest(&input, 2, 2019, true).unwrap();
+        assert_eq!(below.combination.values, vec![366, 1456]);
+        assert_eq!(
+            below.to_string(),
+            "366 (line 3) + 1456 (line 6) = 1822, 197 below 2019"
+        );
+
+        assert_eq!(find_nearest(&input, 3, 10, true), None);
+        assert_eq!(find_nearest(&input, 7, 2020, false), None);
+        assert_eq!(find_nearest::<i64>(&[], 0, 3, false).unwrap().gap(), -3);
+    }
+
+    #[test]
+    fn test_find_nearest_brute_force() {
+        let input = [4, 1, 3, 1, 2, 2, 4, 0, 3, -1, 9];
+
+        for k in 0..=4 {
+            for target in -8..=30 {
+                for below in [false, true] {
+                    let mut expected: Option<i32> = None;
+                    for_each_combination(input.len(), k, |indices| {
+                        let gap = indices.iter().map(|i| input[*i]).sum::<i32>() - target;
+                        let key = |gap: i32| (gap.abs(), gap > 0);
+                        if !(below && gap > 0) && expected.is_none_or(|e| key(gap) < key(e)) {
+                            expected = Some(gap);
+                        }
+                        true
+                    });
+
+                    let nearest = find_nearest(&input, k, target, below);
+                    assert_eq!(
+                        nearest.as_ref().map(|n| n.gap()),
+                        expected.map(i128::from),
+                        "k = {}, target = {}, below = {}",
+                        k,
+                        target,
+                        below
+                    );
+                    if let Some(n) = nearest {
+                        let sum: i32 = n.combination.values.iter().sum();
+                        assert_eq!(i128::from(sum), n.sum);
+                        assert_eq!(n.combination.indices.len(), k);
+                    }
+                }
+            }
+        }
+    }
+
+    fn indices<T>(combinations: &[Combination<T>]) -> Vec<Vec<usize>> {
+        combinations.iter().map(|c| c.indices.clone()).collect()
+    }
+
+    #[test]
+    fn test_find_all_combinations() {
+        let input = [1721, 979, 366, 299, 675, 1456, 1010, 1010, 1010];
+
+        assert_eq!(
+            indices(&find_all_combinations(&input, 2, 2020)),
+            vec![vec![0, 3], vec![6, 7], vec![6, 8], vec![7, 8]]
+        );
+        assert_eq!(
+            find_all_combinations(&input, 3, 2020),
+            vec![Combination {
+                indices: vec![1, 2, 4],
+                values: vec![979, 366, 675],
+            }]
+        );
+        assert_eq!(find_all_combinations(&input, 1, 1010).len(), 3);
+        assert!(find_all_combinations(&input, 2, 1).is_empty());
+    }
+
+    #[test]
+    fn test_find_all_combinations_runs() {
+        let input = [3, 1, 3, 1, 2, 2];
+
+        assert_eq!(
+            indices(&find_all_combinations(&input, 2, 4)),
+            vec![vec![0, 1], vec![0, 3], vec![1, 2], vec![2, 3], vec![4, 5]]
+        );
+        assert_eq!(find_all_combinations(&input, 3, 6).len(), 2 * 2 * 2);
+    }
+
+    #[test]
+    fn test_combination_display() {
+        let all = find_all_combinations(&[1721, 979, 299], 2, 2020);
+
+        assert_eq!(all[0].to_string(), "1721 (line 1) + 299 (line 3)");
+    }
+
+    #[test]
+    fn test_find_all_combinations_brute_force() {
+        let input = [4, 1, 3, 1, 2, 2, 4, 0, 3, -1];
+
+        for k in 1..=4 {
+            for target in -1..=12 {
+                let mut expected = Vec::new();
+                for_each_combination(input.len(), k, |indices| {
+                    if indices.iter().map(|i| input[*i]).sum::<i32>() == target {
+                        expected.push(indices.to_vec());
+                    }
+                    true
+                });
+
+                assert_eq!(
+                    indices(&find_all_combinations(&input, k, target)),
+                    expected,
+                    "k = {}, target = {}",
+                    k,
+                    target
+                );
+            }
+        }
+    }
 }
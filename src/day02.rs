@@ -1,62 +1,123 @@
-use advent_of_code::fmt_dur;
+use crate::{Answer, Error, Result, Solver};
 use lazy_static::lazy_static;
+use log::{debug, trace};
 use regex::Regex;
-use std::env;
-use std::fs;
-use std::time::Instant;
 
-fn is_valid_password(min: usize, max: usize, c: char, pwd: &str) -> bool {
+lazy_static! {
+    static ref RE: Regex =
+        Regex::new(r"(?P<pos1>\d+)-(?P<pos2>\d+) (?P<c>[A-Za-z]): (?P<pwd>.*)").unwrap();
+}
+
+/// Checks that `c` occurs between `min` and `max` times in `pwd`.
+pub fn is_valid_password(min: usize, max: usize, c: char, pwd: &str) -> bool {
     let n = pwd.matches(c).count();
     n >= min && n <= max
 }
 
-fn is_valid_toboggan_password(pos1: usize, pos2: usize, c: char, pwd: &str) -> bool {
-    let char1: char = pwd.chars().nth(pos1 - 1).unwrap();
-    let char2: char = pwd.chars().nth(pos2 - 1).unwrap();
+/// Checks that `c` is at exactly one of the 1-based positions `pos1` and
+/// `pos2`. Positions outside of `pwd` never match.
+pub fn is_valid_toboggan_password(pos1: usize, pos2: usize, c: char, pwd: &str) -> bool {
+    let char_at = |pos: usize| pos.checked_sub(1).and_then(|i| pwd.chars().nth(i));
+
+    (char_at(pos1) == Some(c)) ^ (char_at(pos2) == Some(c))
+}
 
-    (char1 == c) ^ (char2 == c)
+/// A line of the password database: a policy and the password it applies to.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub pos1: usize,
+    pub pos2: usize,
+    pub c: char,
+    pub pwd: String,
 }
 
-fn main() {
-    lazy_static! {
-        static ref RE: Regex =
-            Regex::new(r"(?P<pos1>\d+)-(?P<pos2>\d+) (?P<c>[A-Za-z]): (?P<pwd>.*)").unwrap();
+fn verdict(valid: bool) -> &'static str {
+    if valid {
+        "valid"
+    } else {
+        "invalid"
     }
-    // Read input file
-    let cwd = env::current_dir().unwrap();
-    let filename = cwd.join("inputs/day02.txt");
-    println!("Reading {}", filename.display());
-    let input = fs::read_to_string(filename).expect("Error while reading");
-
-    let mut valid_passwords = Vec::new();
-    let mut valid_toboggan_passwords = Vec::new();
-
-    println!("Checking passwords");
-    let start = Instant::now();
-
-    for line in input.lines() {
-        let caps = RE.captures(line).unwrap();
-        let min = caps["pos1"].parse::<usize>().unwrap();
-        let max = caps["pos2"].parse::<usize>().unwrap();
-        let c = caps["c"].parse::<char>().unwrap();
-        let pwd = caps["pwd"].parse::<String>().unwrap();
-
-        if is_valid_password(min, max, c, &pwd) {
-            valid_passwords.push(pwd.clone());
+}
+
+/// Day 2: Password Philosophy.
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Vec<Entry>> {
+        let mut entries = Vec::new();
+
+        for (n, line) in input.lines().enumerate() {
+            let invalid = |reason: &str| Error::parse(n + 1, format!("{}: {:?}", reason, line));
+            let caps = RE
+                .captures(line)
+                .ok_or_else(|| invalid("invalid password entry"))?;
+            let pos1 = caps["pos1"]
+                .parse::<usize>()
+                .map_err(|_| invalid("invalid position"))?;
+            let pos2 = caps["pos2"]
+                .parse::<usize>()
+                .map_err(|_| invalid("invalid position"))?;
+            if pos1 == 0 || pos2 == 0 {
+                return Err(invalid("positions start at 1"));
+            }
+
+            entries.push(Entry {
+                pos1,
+                pos2,
+                c: caps["c"].chars().next().unwrap(),
+                pwd: caps["pwd"].to_string(),
+            });
         }
 
-        if is_valid_toboggan_password(min, max, c, &pwd) {
-            valid_toboggan_passwords.push(pwd.clone());
+        Ok(entries)
+    }
+
+    fn part1(entries: &Vec<Entry>) -> Result<Answer> {
+        let mut valid = 0;
+        for (n, e) in entries.iter().enumerate() {
+            let ok = is_valid_password(e.pos1, e.pos2, e.c, &e.pwd);
+            trace!(
+                "line {}: {:?} has {} {:?}, needs {} to {}: {}",
+                n + 1,
+                e.pwd,
+                e.pwd.matches(e.c).count(),
+                e.c,
+                e.pos1,
+                e.pos2,
+                verdict(ok)
+            );
+            valid += ok as usize;
+        }
+        debug!("{} of {} passwords are valid", valid, entries.len());
+        Ok(valid.into())
+    }
+
+    fn part2(entries: &Vec<Entry>) -> Result<Answer> {
+        let mut valid = 0;
+        for (n, e) in entries.iter().enumerate() {
+            let ok = is_valid_toboggan_password(e.pos1, e.pos2, e.c, &e.pwd);
+            let char_at = |pos: usize| match e.pwd.chars().nth(pos - 1) {
+                Some(c) => format!("{:?}", c),
+                None => "nothing".to_string(),
+            };
+            trace!(
+                "line {}: {:?} has {} and {} at {} and {}, needs one {:?}: {}",
+                n + 1,
+                e.pwd,
+                char_at(e.pos1),
+                char_at(e.pos2),
+                e.pos1,
+                e.pos2,
+                e.c,
+                verdict(ok)
+            );
+            valid += ok as usize;
         }
+        debug!("{} of {} passwords are valid", valid, entries.len());
+        Ok(valid.into())
     }
-    let dur = start.elapsed();
-
-    println!("Number of valid passwords: {}", valid_passwords.len());
-    println!(
-        "Number of valid toboggan passwords: {}",
-        valid_toboggan_passwords.len()
-    );
-    println!("Took {}", fmt_dur(dur));
 }
 
 #[cfg(test)]
@@ -70,7 +131,7 @@ mod tests {
         let c: char = 'a';
         let pwd = "abcde";
 
-        assert_eq!(is_valid_password(min, max, c, pwd), true);
+        assert!(is_valid_password(min, max, c, pwd));
     }
 
     #[test]
@@ -80,7 +141,7 @@ mod tests {
         let c: char = 'b';
         let pwd = "cdefg";
 
-        assert_eq!(is_valid_password(min, max, c, pwd), false);
+        assert!(!is_valid_password(min, max, c, pwd));
     }
 
     #[test]
@@ -90,7 +151,7 @@ mod tests {
         let c: char = 'c';
         let pwd = "ccccccccc";
 
-        assert_eq!(is_valid_password(min, max, c, pwd), true);
+        assert!(is_valid_password(min, max, c, pwd));
     }
 
     #[test]
@@ -100,7 +161,7 @@ mod tests {
         let c: char = 'a';
         let pwd = "abcde";
 
-        assert_eq!(is_valid_toboggan_password(min, max, c, pwd), true);
+        assert!(is_valid_toboggan_password(min, max, c, pwd));
     }
 
     #[test]
@@ -110,7 +171,7 @@ mod tests {
         let c: char = 'b';
         let pwd = "cdefg";
 
-        assert_eq!(is_valid_toboggan_password(min, max, c, pwd), false);
+        assert!(!is_valid_toboggan_password(min, max, c, pwd));
     }
 
     #[test]
@@ -120,6 +181,31 @@ mod tests {
         let c: char = 'c';
         let pwd = "ccccccccc";
 
-        assert_eq!(is_valid_toboggan_password(min, max, c, pwd), false);
+        assert!(!is_valid_toboggan_password(min, max, c, pwd));
+    }
+
+    #[test]
+    fn test_solver() {
+        let input = Day02::parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+
+        assert_eq!(Day02::part1(&input).unwrap(), Answer::Number(2));
+        assert_eq!(Day02::part2(&input).unwrap(), Answer::Number(1));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = Day02::parse("1-3 a: abcde\n1-3 b cdefg").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line 2: invalid password entry: \"1-3 b cdefg\""
+        );
+        assert!(Day02::parse("0-3 a: abcde").is_err());
+    }
+
+    #[test]
+    fn test_part2_out_of_range() {
+        assert!(is_valid_toboggan_password(1, 9, 'a', "abcde"));
+        assert!(!is_valid_toboggan_password(8, 9, 'a', "abcde"));
     }
 }
@@ -1,24 +1,40 @@
-use advent_of_code::fmt_dur;
-use std::env;
-use std::fs;
-use std::time::Instant;
-
-const TREE_SYMBOL: char = '#';
-
-fn traverse(slope: Vec<&str>, start_pos: (usize, usize), route: (usize, usize)) -> i32 {
+use crate::timer::span;
+use crate::{Answer, Error, Result, Solver};
+use log::{debug, trace};
+
+pub const TREE_SYMBOL: char = '#';
+pub const OPEN_SYMBOL: char = '.';
+
+/// Counts the trees hit when moving `route` (right, down) from `start_pos`
+/// (line, tile) to the bottom of `slope`, which repeats to the right.
+pub fn traverse<S: AsRef<str>>(
+    slope: &[S],
+    start_pos: (usize, usize),
+    route: (usize, usize),
+) -> i32 {
     let mut tree_counter = 0;
     let (right, down) = route;
     let (mut line, mut tile) = start_pos;
-    let slope_width = slope[line].chars().count();
-    while line < slope.len() - 1 {
-        line += down;
-        tile += right;
-        if tile >= slope_width {
-            tile -= slope_width;
-        }
+    let slope_width = match slope.get(line) {
+        Some(l) => l.as_ref().chars().count(),
+        None => return 0,
+    };
+    if slope_width == 0 || down == 0 {
+        return 0;
+    }
 
-        let cur_symbol = slope[line].chars().nth(tile).unwrap();
-        if cur_symbol == TREE_SYMBOL {
+    while line + down < slope.len() {
+        line += down;
+        tile = (tile + right) % slope_width;
+
+        let cur_symbol = slope[line].as_ref().chars().nth(tile);
+        if cur_symbol == Some(TREE_SYMBOL) {
+            trace!(
+                "route {:?}: tree at line {}, column {}",
+                route,
+                line + 1,
+                tile + 1
+            );
             tree_counter += 1;
         }
     }
@@ -26,154 +42,136 @@ fn traverse(slope: Vec<&str>, start_pos: (usize, usize), route: (usize, usize))
     tree_counter
 }
 
-fn main() {
-    // // Read input file
-    let cwd = env::current_dir().unwrap();
-    let filename = cwd.join("inputs/day03.txt");
-    println!("Reading {}", filename.display());
-    let input = fs::read_to_string(filename).expect("Error while reading");
-    let lines: Vec<&str> = input.lines().collect();
-
-    println!("Running Part 1");
-    let part1_start = Instant::now();
-    let start_pos = (0, 0);
-    let route = (3, 1);
-
-    let part1 = traverse(lines.clone(), start_pos, route);
-    let part1_dur = part1_start.elapsed();
-    println!("Number of trees: {}", part1);
-    println!("Took {}", fmt_dur(part1_dur));
-
-    println!("Running Part 2");
-    let part2_start = Instant::now();
-    let start_pos = (0, 0);
-    let routes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
-    let mut results = Vec::new();
-
-    for route in routes.iter() {
-        let res = traverse(lines.clone(), start_pos, *route);
-        results.push(res);
+/// Day 3: Toboggan Trajectory.
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>> {
+        let lines: Vec<String> = input.lines().map(String::from).collect();
+        let width = match lines.first() {
+            Some(first) if !first.is_empty() => first.chars().count(),
+            _ => return Err(Error::InvalidInput("the map is empty".to_string())),
+        };
+
+        for (n, line) in lines.iter().enumerate() {
+            if let Some(c) = line
+                .chars()
+                .find(|c| *c != TREE_SYMBOL && *c != OPEN_SYMBOL)
+            {
+                return Err(Error::parse(n + 1, format!("unexpected {:?}", c)));
+            }
+            if line.chars().count() != width {
+                return Err(Error::InvalidInput(format!(
+                    "line {} is not {} wide like the first line",
+                    n + 1,
+                    width
+                )));
+            }
+        }
+
+        Ok(lines)
+    }
+
+    fn part1(lines: &Vec<String>) -> Result<Answer> {
+        let start_pos = (0, 0);
+        let route = (3, 1);
+
+        let trees = traverse(lines, start_pos, route);
+        debug!("route {:?} hits {} trees", route, trees);
+        Ok(trees.into())
     }
 
-    let part2_dur = part2_start.elapsed();
-    println!(
-        "Number of trees: {}",
-        results.iter().map(|x| *x as u32).product::<u32>()
-    );
-    println!("Took {}", fmt_dur(part2_dur));
+    fn part2(lines: &Vec<String>) -> Result<Answer> {
+        let start_pos = (0, 0);
+        let routes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        let mut results = Vec::new();
+
+        for route in routes.iter() {
+            let _t = span("route");
+            let res = traverse(lines, start_pos, *route);
+            debug!("route {:?} hits {} trees", route, res);
+            results.push(res);
+        }
+
+        Ok(results.iter().map(|x| *x as i64).product::<i64>().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SLOPE: [&str; 11] = [
+        "..##.......",
+        "#...#...#..",
+        ".#....#..#.",
+        "..#.#...#.#",
+        ".#...##..#.",
+        "..#.##.....",
+        ".#.#.#....#",
+        ".#........#",
+        "#.##...#...",
+        "#...##....#",
+        ".#..#...#.#",
+    ];
+
     #[test]
     fn test_3right_1down() {
-        let slope = vec![
-            "..##.......",
-            "#...#...#..",
-            ".#....#..#.",
-            "..#.#...#.#",
-            ".#...##..#.",
-            "..#.##.....",
-            ".#.#.#....#",
-            ".#........#",
-            "#.##...#...",
-            "#...##....#",
-            ".#..#...#.#",
-        ];
         let start_pos = (0, 0);
         let route = (3, 1);
 
-        let res = traverse(slope.clone(), start_pos, route);
+        let res = traverse(&SLOPE, start_pos, route);
         assert_eq!(res, 7);
     }
 
     #[test]
     fn test_1right_1down() {
-        let slope = vec![
-            "..##.......",
-            "#...#...#..",
-            ".#....#..#.",
-            "..#.#...#.#",
-            ".#...##..#.",
-            "..#.##.....",
-            ".#.#.#....#",
-            ".#........#",
-            "#.##...#...",
-            "#...##....#",
-            ".#..#...#.#",
-        ];
         let start_pos = (0, 0);
         let route = (1, 1);
 
-        let res = traverse(slope.clone(), start_pos, route);
+        let res = traverse(&SLOPE, start_pos, route);
         assert_eq!(res, 2);
     }
 
     #[test]
     fn test_5right_1down() {
-        let slope = vec![
-            "..##.......",
-            "#...#...#..",
-            ".#....#..#.",
-            "..#.#...#.#",
-            ".#...##..#.",
-            "..#.##.....",
-            ".#.#.#....#",
-            ".#........#",
-            "#.##...#...",
-            "#...##....#",
-            ".#..#...#.#",
-        ];
         let start_pos = (0, 0);
         let route = (5, 1);
 
-        let res = traverse(slope.clone(), start_pos, route);
+        let res = traverse(&SLOPE, start_pos, route);
         assert_eq!(res, 3);
     }
 
     #[test]
     fn test_7right_1down() {
-        let slope = vec![
-            "..##.......",
-            "#...#...#..",
-            ".#....#..#.",
-            "..#.#...#.#",
-            ".#...##..#.",
-            "..#.##.....",
-            ".#.#.#....#",
-            ".#........#",
-            "#.##...#...",
-            "#...##....#",
-            ".#..#...#.#",
-        ];
         let start_pos = (0, 0);
         let route = (7, 1);
 
-        let res = traverse(slope.clone(), start_pos, route);
+        let res = traverse(&SLOPE, start_pos, route);
         assert_eq!(res, 4);
     }
 
     #[test]
     fn test_1right_2down() {
-        let slope = vec![
-            "..##.......",
-            "#...#...#..",
-            ".#....#..#.",
-            "..#.#...#.#",
-            ".#...##..#.",
-            "..#.##.....",
-            ".#.#.#....#",
-            ".#........#",
-            "#.##...#...",
-            "#...##....#",
-            ".#..#...#.#",
-        ];
         let start_pos = (0, 0);
         let route = (1, 2);
 
-        let res = traverse(slope.clone(), start_pos, route);
+        let res = traverse(&SLOPE, start_pos, route);
+        assert_eq!(res, 2);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Day03::parse("").is_err());
+        assert!(Day03::parse("..#\n.x.").is_err());
+        assert!(Day03::parse("..#\n.#").is_err());
+    }
+
+    #[test]
+    fn test_uneven_end() {
+        let res = traverse(&SLOPE[..10], (0, 0), (1, 2));
         assert_eq!(res, 2);
     }
 }
@@ -1,50 +1,70 @@
-use advent_of_code::fmt_dur;
+use crate::{Answer, Error, Solver};
+use log::{debug, trace};
 use regex::Regex;
 use std::collections::HashMap;
-use std::env;
-use std::fs;
+use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
-use std::time::Instant;
+
+/// A validation rule of a passport field.
+type Rule = fn(&Passport) -> bool;
 
 #[derive(Debug, PartialEq)]
-struct Passport {
-    byr: u16,
-    eyr: u16,
-    iyr: u16,
-    cid: Option<u8>,
-    pid: String,
-    ecl: String,
-    hcl: String,
-    hgt: String,
+pub struct Passport {
+    pub byr: u16,
+    pub eyr: u16,
+    pub iyr: u16,
+    pub cid: Option<u8>,
+    pub pid: String,
+    pub ecl: String,
+    pub hcl: String,
+    pub hgt: String,
 }
 
 impl Passport {
-    fn is_valid(&self) -> bool {
-        self.has_valid_ecl()
-            && self.has_valid_hcl()
-            && self.has_valid_pid()
-            && self.has_valid_byr()
-            && self.has_valid_eyr()
-            && self.has_valid_iyr()
-            && self.has_valid_hgt()
+    /// Checks every field except `cid` against the stricter validation rules.
+    pub fn is_valid(&self) -> bool {
+        self.first_failing_rule().is_none()
+    }
+
+    /// The first of the stricter validation rules this passport breaks.
+    pub fn first_failing_rule(&self) -> Option<&'static str> {
+        let rules: [(Rule, &str); 7] = [
+            (
+                Passport::has_valid_ecl,
+                "ecl must be amb, blu, brn, gry, grn, hzl or oth",
+            ),
+            (Passport::has_valid_hcl, "hcl must be # and 6 hex digits"),
+            (Passport::has_valid_pid, "pid must have 9 digits"),
+            (Passport::has_valid_byr, "byr must be 1920 to 2002"),
+            (Passport::has_valid_eyr, "eyr must be 2020 to 2030"),
+            (Passport::has_valid_iyr, "iyr must be 2010 to 2020"),
+            (
+                Passport::has_valid_hgt,
+                "hgt must be 150 to 193cm or 59 to 76in",
+            ),
+        ];
+        rules
+            .iter()
+            .find(|(is_valid, _)| !is_valid(self))
+            .map(|(_, rule)| *rule)
     }
 
     fn has_valid_hgt(&self) -> bool {
-        const MIN_CM: u8 = 150;
-        const MAX_CM: u8 = 193;
-        const MIN_IN: u8 = 59;
-        const MAX_IN: u8 = 76;
+        const MIN_CM: u16 = 150;
+        const MAX_CM: u16 = 193;
+        const MIN_IN: u16 = 59;
+        const MAX_IN: u16 = 76;
 
         let hgt_re = Regex::new(r"(?P<measure>\d{2,3})(?P<unit>in|cm)").unwrap();
         match hgt_re.captures(&self.hgt) {
             Some(caps) => {
-                let measure = caps["measure"].parse::<u8>().unwrap();
+                let measure = caps["measure"].parse::<u16>().unwrap();
                 let unit = &caps["unit"];
 
                 match unit {
-                    "cm" => measure >= MIN_CM && measure <= MAX_CM,
-                    "in" => measure >= MIN_IN && measure <= MAX_IN,
+                    "cm" => (MIN_CM..=MAX_CM).contains(&measure),
+                    "in" => (MIN_IN..=MAX_IN).contains(&measure),
                     _ => false,
                 }
             }
@@ -87,11 +107,28 @@ impl Passport {
 }
 
 #[derive(Debug, PartialEq)]
-enum PassportFromStrError {
+pub enum PassportFromStrError {
     Int(ParseIntError),
     MissingKey { missing_key: String },
+    InvalidField { field: String },
 }
 
+impl fmt::Display for PassportFromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassportFromStrError::Int(cause) => write!(f, "invalid number: {}", cause),
+            PassportFromStrError::MissingKey { missing_key } => {
+                write!(f, "missing key: {}", missing_key)
+            }
+            PassportFromStrError::InvalidField { field } => {
+                write!(f, "field is not key:value: {:?}", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PassportFromStrError {}
+
 impl From<ParseIntError> for PassportFromStrError {
     fn from(cause: ParseIntError) -> PassportFromStrError {
         PassportFromStrError::Int(cause)
@@ -107,6 +144,11 @@ impl FromStr for Passport {
 
         for attr in s.split_whitespace() {
             let spl_str = attr.split(':').collect::<Vec<&str>>();
+            if spl_str.len() != 2 {
+                return Err(PassportFromStrError::InvalidField {
+                    field: attr.to_string(),
+                });
+            }
             passport.insert(spl_str[0], Some(spl_str[1]));
         }
 
@@ -141,34 +183,47 @@ impl FromStr for Passport {
     }
 }
 
-fn main() {
-    // // Read input file
-    let cwd = env::current_dir().unwrap();
-    let filename = cwd.join("inputs/day04.txt");
-    println!("Reading {}", filename.display());
-    let input = fs::read_to_string(filename).expect("Error while reading");
+/// Day 4: Passport Processing.
+pub struct Day04;
 
-    println!("Running Part 1");
-    let part1_start = Instant::now();
+impl Solver for Day04 {
+    type Input = Vec<Passport>;
 
-    let passports: Vec<Passport> = input
-        .split("\n\n")
-        .filter_map(|p| Passport::from_str(p).ok())
-        .collect();
+    /// Keeps the passports with all required fields, but fails on fields
+    /// that are not `key:value` at all.
+    fn parse(input: &str) -> crate::Result<Vec<Passport>> {
+        let mut passports = Vec::new();
+        let mut line = 1;
 
-    let part1_dur = part1_start.elapsed();
-    println!("Valid passports: {}", passports.len());
-    println!("Took {}", fmt_dur(part1_dur));
+        for p in input.split("\n\n") {
+            match Passport::from_str(p) {
+                Ok(passport) => passports.push(passport),
+                Err(err @ PassportFromStrError::InvalidField { .. }) => {
+                    return Err(Error::parse(line, err));
+                }
+                Err(err) => debug!("skipping passport at line {}: {}", line, err),
+            }
+            line += p.matches('\n').count() + 2;
+        }
 
-    println!("Running Part 2");
-    let part2_start = Instant::now();
+        Ok(passports)
+    }
 
-    let validated_passports: Vec<Passport> =
-        passports.into_iter().filter(|p| p.is_valid()).collect();
+    fn part1(passports: &Vec<Passport>) -> crate::Result<Answer> {
+        Ok(passports.len().into())
+    }
 
-    let part2_dur = part2_start.elapsed();
-    println!("Valid passports: {}", validated_passports.len());
-    println!("Took {}", fmt_dur(part2_dur));
+    fn part2(passports: &Vec<Passport>) -> crate::Result<Answer> {
+        let mut valid = 0;
+        for (n, p) in passports.iter().enumerate() {
+            match p.first_failing_rule() {
+                Some(rule) => trace!("passport {} (pid {}): {}", n + 1, p.pid, rule),
+                None => valid += 1,
+            }
+        }
+        debug!("{} of {} passports are valid", valid, passports.len());
+        Ok(valid.into())
+    }
 }
 
 #[cfg(test)]
@@ -247,7 +302,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
         hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -258,7 +313,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
         hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -269,7 +324,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -280,7 +335,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
     #[test]
     fn test_hcl_without_hash() {
@@ -290,7 +345,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -301,7 +356,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -312,7 +367,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -323,7 +378,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -334,7 +389,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -345,7 +400,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -356,7 +411,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -367,7 +422,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -378,7 +433,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -389,7 +444,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -400,7 +455,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -411,7 +466,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -422,7 +477,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:179cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -433,7 +488,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:60in";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -444,7 +499,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:190in";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -455,7 +510,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:190cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), true);
+        assert!(result.is_valid());
     }
 
     #[test]
@@ -466,7 +521,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:200cm";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -477,7 +532,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
     hgt:190";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -486,7 +541,7 @@ byr:1937 iyr:2017 cid:147 hgt:183cm";
 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -496,7 +551,7 @@ hcl:#602927 eyr:1967 hgt:170cm
 ecl:grn pid:012533040 byr:1946";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -505,7 +560,7 @@ ecl:grn pid:012533040 byr:1946";
 ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
     }
 
     #[test]
@@ -515,6 +570,56 @@ eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:2007";
         let result = Passport::from_str(string).unwrap();
 
-        assert_eq!(result.is_valid(), false);
+        assert!(!result.is_valid());
+    }
+
+    #[test]
+    fn test_from_str_invalid_field() {
+        let result = Passport::from_str("ecl:gry pid");
+        let expected = Err(PassportFromStrError::InvalidField {
+            field: "pid".to_string(),
+        });
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_parse_invalid_field() {
+        let string = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb
+cid:350 eyr2023";
+        let err = Day04::parse(string).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line 4: field is not key:value: \"eyr2023\""
+        );
+    }
+
+    #[test]
+    fn test_hgt_out_of_range() {
+        let string = "hcl:#ae17e1 iyr:2013
+    eyr:2024
+    ecl:gry pid:123456789 byr:1931
+    hgt:300cm";
+        let result = Passport::from_str(string).unwrap();
+
+        assert!(!result.is_valid());
+    }
+
+    #[test]
+    fn test_first_failing_rule() {
+        let string = "hcl:#ae17e1 iyr:2013
+    eyr:2024
+    ecl:red pid:123456789 byr:1931
+    hgt:300cm";
+        let result = Passport::from_str(string).unwrap();
+
+        assert_eq!(
+            result.first_failing_rule(),
+            Some("ecl must be amb, blu, brn, gry, grn, hzl or oth")
+        );
     }
 }
@@ -0,0 +1,277 @@
+use crate::input::default_path;
+use crate::{Error, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Environment variable holding the adventofcode.com session cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable overriding the server the inputs are fetched from.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+/// Environment variable overriding the path of the config file.
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u16 = 2020;
+
+/// Settings for fetching inputs.
+///
+/// Read from `aoc.conf` in the crate root, or the file in `AOC_CONFIG`, with
+/// one `key = value` per line for the keys `session`, `base_url` and `year`.
+/// The environment variables take precedence over the file.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub year: u16,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            year: DEFAULT_YEAR,
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Config> {
+        let mut config = Config::default();
+
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(Error::parse(n + 1, format!("not key = value: {:?}", line))),
+            };
+            match key {
+                "session" => config.session = Some(value.to_string()),
+                "base_url" => config.base_url = value.to_string(),
+                "year" => {
+                    config.year = value
+                        .parse()
+                        .map_err(|e| Error::parse(n + 1, format!("invalid year: {}", e)))?
+                }
+                _ => return Err(Error::parse(n + 1, format!("unknown key {:?}", key))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    match env::var_os(CONFIG_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("aoc.conf"),
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Config> {
+        let path = config_path();
+        let mut config = if path.exists() {
+            let config = fs::read_to_string(&path).map_err(|cause| Error::io(&path, cause))?;
+            config
+                .parse()
+                .map_err(|e| Error::Other(format!("{}: {}", path.display(), e)))?
+        } else {
+            Config::default()
+        };
+
+        if let Ok(session) = env::var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var(BASE_URL_VAR) {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    pub fn input_url(&self, day: u8) -> String {
+        format!(
+            "{}/{}/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            self.year,
+            day
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the input of `day` to `path`, unless it is already there. An
+/// empty file, like the placeholder `aoc new` creates, is not an input yet.
+pub fn fetch_input(config: &Config, day: u8, path: &Path) -> Result<Fetched> {
+    if fs::metadata(path).is_ok_and(|meta| meta.len() > 0) {
+        return Ok(Fetched::Cached(path.to_path_buf()));
+    }
+
+    let session = config.session.as_ref().ok_or_else(|| {
+        Error::Fetch(format!(
+            "no session token, set {} or session in {}",
+            SESSION_VAR,
+            config_path().display()
+        ))
+    })?;
+    let url = config.input_url(day);
+    let input = ureq::get(&url)
+        .set("Cookie", &format!("session={}", session))
+        .set("User-Agent", "github.com/johangu/advent-of-code-2020")
+        .call()
+        .map_err(|e| Error::Fetch(format!("could not fetch {}: {}", url, e)))?
+        .into_string()?;
+
+    // Write next to the target first, so an interrupted download is not
+    // mistaken for a cached input.
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|cause| Error::io(dir, cause))?;
+    }
+    let partial = path.with_extension("part");
+    fs::write(&partial, input).map_err(|cause| Error::io(&partial, cause))?;
+    fs::rename(&partial, path).map_err(|cause| Error::io(path, cause))?;
+
+    Ok(Fetched::Downloaded(path.to_path_buf()))
+}
+
+/// Downloads the input of `day` to the input directory.
+pub fn fetch_default(config: &Config, day: u8) -> Result<Fetched> {
+    fetch_input(config, day, &default_path(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Answers a single request with `body` and returns the request head.
+    fn serve_once(body: &'static str) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let head: Vec<String> = BufReader::new(stream.try_clone().unwrap())
+                .lines()
+                .map(|l| l.unwrap())
+                .take_while(|l| !l.is_empty())
+                .collect();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            head
+        });
+        (url, handle)
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("aoc-download-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_config_from_str() {
+        let config: Config = "# comment\nsession = abc123\nyear=2021\n".parse().unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                session: Some("abc123".to_string()),
+                base_url: DEFAULT_BASE_URL.to_string(),
+                year: 2021,
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_invalid() {
+        assert!("session abc".parse::<Config>().is_err());
+        assert!("token = abc".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn test_input_url() {
+        let config = Config {
+            base_url: "http://localhost:8080/".to_string(),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.input_url(3),
+            "http://localhost:8080/2020/day/3/input"
+        );
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let (base_url, server) = serve_once("1721\n979\n");
+        let config = Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            year: 2020,
+        };
+        let path = temp_path("day01.txt");
+
+        let fetched = fetch_input(&config, 1, &path).unwrap();
+        let head = server.join().unwrap();
+        let cached = fetch_input(&config, 1, &path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(fetched, Fetched::Downloaded(path.clone()));
+        assert_eq!(cached, Fetched::Cached(path));
+        assert_eq!(content, "1721\n979\n");
+        assert_eq!(head[0], "GET /2020/day/1/input HTTP/1.1");
+        assert!(head.iter().any(|l| l == "Cookie: session=abc123"));
+    }
+
+    #[test]
+    fn test_fetch_over_empty_file() {
+        let (base_url, server) = serve_once("1721\n");
+        let config = Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            year: 2020,
+        };
+        let path = temp_path("day05.txt");
+        fs::write(&path, "").unwrap();
+
+        let fetched = fetch_input(&config, 5, &path).unwrap();
+        server.join().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(fetched, Fetched::Downloaded(path));
+        assert_eq!(content, "1721\n");
+    }
+
+    #[test]
+    fn test_fetch_without_session() {
+        let config = Config::default();
+        let path = temp_path("day02.txt");
+
+        assert!(matches!(
+            fetch_input(&config, 2, &path),
+            Err(Error::Fetch(_))
+        ));
+        assert!(!path.exists());
+    }
+}
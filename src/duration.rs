@@ -0,0 +1,201 @@
+use crate::Error;
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+const UNITS: [(&str, u128); 6] = [
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("µs", 1_000),
+    ("ns", 1),
+];
+
+/// Units shown when no precision is given.
+pub const DEFAULT_UNITS: usize = 2;
+
+/// A duration displayed as its largest units, like `1m 2s` or `12ms 3µs`.
+///
+/// The precision of the format sets how many consecutive units are shown,
+/// starting at the largest non-zero one, so `{:.3}` gives `1m 2s 300ms`.
+/// Smaller units are truncated and zero units are left out. Width, fill and
+/// alignment pad the units like a string. Parsing accepts the same format,
+/// with `us` allowed for `µs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl From<Duration> for HumanDuration {
+    fn from(dur: Duration) -> HumanDuration {
+        HumanDuration(dur)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(dur: HumanDuration) -> Duration {
+        dur.0
+    }
+}
+
+impl HumanDuration {
+    fn units(self, max_units: usize) -> String {
+        let mut rem = self.0.as_nanos();
+        let first = match UNITS.iter().position(|(_, ns)| rem >= *ns) {
+            Some(first) => first,
+            None => return "0ns".to_string(),
+        };
+
+        let mut parts = Vec::new();
+        for (unit, ns) in UNITS[first..].iter().take(max_units) {
+            let n = rem / ns;
+            rem %= ns;
+            if n > 0 {
+                parts.push(format!("{}{}", n, unit));
+            }
+        }
+        parts.join(" ")
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = self.units(f.precision().unwrap_or(DEFAULT_UNITS).max(1));
+        // Not `Formatter::pad`, which would cut the text to the precision.
+        let pad = f
+            .width()
+            .map_or(0, |width| width.saturating_sub(text.chars().count()));
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Right) => (pad, 0),
+            Some(fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+            _ => (0, pad),
+        };
+
+        for _ in 0..before {
+            f.write_char(f.fill())?;
+        }
+        f.write_str(&text)?;
+        for _ in 0..after {
+            f.write_char(f.fill())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HumanDuration, Error> {
+        let invalid = || Error::Other(format!("invalid duration: {:?}", s));
+        let mut total: u128 = 0;
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let n: u128 = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+
+            let unit_len = rest
+                .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let unit = match &rest[..unit_len] {
+                "us" => "µs",
+                unit => unit,
+            };
+            let (_, ns) = UNITS.iter().find(|(u, _)| *u == unit).ok_or_else(invalid)?;
+            total = n
+                .checked_mul(*ns)
+                .and_then(|n| total.checked_add(n))
+                .ok_or_else(invalid)?;
+            rest = rest[unit_len..].trim_start();
+        }
+
+        let secs = u64::try_from(total / 1_000_000_000).map_err(|_| invalid())?;
+        Ok(HumanDuration(Duration::new(
+            secs,
+            (total % 1_000_000_000) as u32,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn human(s: &str) -> HumanDuration {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_display() {
+        let dur = HumanDuration(Duration::new(62, 300_004_005));
+
+        assert_eq!(dur.to_string(), "1m 2s");
+        assert_eq!(format!("{:.3}", dur), "1m 2s 300ms");
+        assert_eq!(format!("{:.6}", dur), "1m 2s 300ms 4µs 5ns");
+        assert_eq!(format!("{:.1}", dur), "1m");
+    }
+
+    #[test]
+    fn test_display_width() {
+        let dur = HumanDuration(Duration::from_nanos(12_003_000));
+
+        assert_eq!(format!("{:>10}", dur), "  12ms 3µs");
+        assert_eq!(format!("{:10}|", dur), "12ms 3µs  |");
+        assert_eq!(format!("{:*^12.1}", dur), "****12ms****");
+        assert_eq!(format!("{:3}", dur), "12ms 3µs");
+    }
+
+    #[test]
+    fn test_display_small() {
+        assert_eq!(
+            HumanDuration(Duration::from_nanos(12_003_000)).to_string(),
+            "12ms 3µs"
+        );
+        assert_eq!(
+            HumanDuration(Duration::from_nanos(999)).to_string(),
+            "999ns"
+        );
+        assert_eq!(HumanDuration(Duration::default()).to_string(), "0ns");
+    }
+
+    #[test]
+    fn test_display_skips_zero_units() {
+        let dur = HumanDuration(Duration::from_secs(3600 + 5));
+
+        assert_eq!(format!("{:.3}", dur), "1h 5s");
+        assert_eq!(dur.to_string(), "1h");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(human("1m 2s 300ms").0, Duration::from_millis(62_300));
+        assert_eq!(human("1m2s").0, Duration::from_secs(62));
+        assert_eq!(human("12ms 3µs").0, Duration::from_micros(12_003));
+        assert_eq!(human("3us").0, Duration::from_micros(3));
+        assert_eq!(human("2h").0, Duration::from_secs(7200));
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!("".parse::<HumanDuration>().is_err());
+        assert!("12".parse::<HumanDuration>().is_err());
+        assert!("12 ms".parse::<HumanDuration>().is_err());
+        assert!("1.5s".parse::<HumanDuration>().is_err());
+        assert!("5d".parse::<HumanDuration>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dur = HumanDuration(Duration::new(3725, 6_007_008));
+        let shown = format!("{:.6}", dur);
+
+        assert_eq!(human(&shown), dur);
+        assert_eq!(human(&dur.to_string()).to_string(), dur.to_string());
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed, `path` is `None` for stdin and
+    /// other streams.
+    Io {
+        path: Option<PathBuf>,
+        cause: io::Error,
+    },
+    /// A line of the input could not be parsed, `line` is 1-based.
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// The input parsed, but does not have the shape a solver needs.
+    InvalidInput(String),
+    /// The input has no answer for the part.
+    NoSolution,
+    /// A result does not fit the integer type it is computed in.
+    Overflow(String),
+    /// Downloading an input failed.
+    Fetch(String),
+    /// An error while solving the given day.
+    Day {
+        day: u8,
+        cause: Box<Error>,
+    },
+    Other(String),
+}
+
+impl Error {
+    pub fn io<P: Into<PathBuf>>(path: P, cause: io::Error) -> Error {
+        Error::Io {
+            path: Some(path.into()),
+            cause,
+        }
+    }
+
+    pub fn parse<M: fmt::Display>(line: usize, message: M) -> Error {
+        Error::Parse {
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    /// Wraps the error with the day it happened on.
+    pub fn in_day(self, day: u8) -> Error {
+        match self {
+            Error::Day { .. } => self,
+            cause => Error::Day {
+                day,
+                cause: Box::new(cause),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                cause,
+            } => write!(f, "{}: {}", path.display(), cause),
+            Error::Io { path: None, cause } => write!(f, "{}", cause),
+            Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Error::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            Error::NoSolution => write!(f, "no solution found"),
+            Error::Overflow(what) => write!(f, "{} overflows", what),
+            Error::Fetch(message) => write!(f, "{}", message),
+            Error::Day { day, cause } => write!(f, "day {:02}: {}", day, cause),
+            Error::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { cause, .. } => Some(cause),
+            Error::Day { cause, .. } => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(cause: io::Error) -> Error {
+        Error::Io { path: None, cause }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(cause: ParseIntError) -> Error {
+        Error::Other(cause.to_string())
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Error {
+        Error::Other(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::io("inputs/day01.txt", io::ErrorKind::NotFound.into());
+
+        assert_eq!(err.to_string(), "inputs/day01.txt: entity not found");
+        assert_eq!(
+            Error::parse(3, "invalid digit").to_string(),
+            "line 3: invalid digit"
+        );
+    }
+
+    #[test]
+    fn test_in_day() {
+        let err = Error::NoSolution.in_day(1).in_day(2);
+
+        assert_eq!(err.to_string(), "day 01: no solution found");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::answers::Mismatch;
+use crate::{get_day, solve_with_deadline, Error, Outcome, Result, DEFAULT_DEADLINE};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An example input with the answers it should produce.
+///
+/// Examples live in `examples/dayNN/<name>.txt`, with the expected answers in
+/// `examples/dayNN/<name>.answers` as one `<part> <answer>` line per part.
+/// Parts without an expected answer are not checked.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+    pub expected: Vec<(u8, String)>,
+}
+
+/// `examples/` in the crate root.
+pub fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+fn parse_expected(s: &str) -> Result<Vec<(u8, String)>> {
+    let mut expected = Vec::new();
+
+    for (n, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.splitn(2, ' ');
+        match (fields.next().map(str::parse::<u8>), fields.next()) {
+            (Some(Ok(part @ 1..=2)), Some(answer)) => {
+                expected.push((part, answer.trim().to_string()))
+            }
+            _ => return Err(Error::parse(n + 1, format!("invalid answer: {:?}", line))),
+        }
+    }
+
+    Ok(expected)
+}
+
+fn day_of_dir(path: &Path) -> Option<u8> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_prefix("day")?.parse().ok()
+}
+
+/// Finds all examples below `dir`, ordered by day and name.
+pub fn discover(dir: &Path) -> Result<Vec<Example>> {
+    let mut examples = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(|cause| Error::io(dir, cause))? {
+        let day_dir = entry?.path();
+        let day = match day_of_dir(&day_dir) {
+            Some(day) if day_dir.is_dir() => day,
+            _ => continue,
+        };
+
+        for entry in fs::read_dir(&day_dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+
+            let answers_path = path.with_extension("answers");
+            let expected = fs::read_to_string(&answers_path)
+                .map_err(|cause| Error::io(&answers_path, cause))?;
+            examples.push(Example {
+                day,
+                name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+                expected: parse_expected(&expected)
+                    .map_err(|e| format!("{}: {}", answers_path.display(), e))?,
+                path,
+            });
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+impl Example {
+    /// Solves the example and compares the answers to the expected ones. Each
+    /// part runs under the deadline of its day, like in `run_day`.
+    pub fn check(&self) -> Result<Vec<Mismatch>> {
+        let day = get_day(self.day)
+            .ok_or_else(|| format!("no solver registered for day {}", self.day))?;
+        let input = fs::read_to_string(&self.path).map_err(|cause| Error::io(&self.path, cause))?;
+        let parsed = (day.parse)(&input)?;
+        let deadline = day.deadline.unwrap_or(DEFAULT_DEADLINE);
+
+        let mut mismatches = Vec::new();
+        for (part, expected) in self.expected.iter() {
+            let actual = match solve_with_deadline(&parsed, *part, deadline)?.0 {
+                Outcome::Answer(answer) => answer.to_string(),
+                Outcome::Error(message) => format!("error: {}", message),
+                Outcome::Timeout => "TIMEOUT".to_string(),
+            };
+            if *expected != actual {
+                mismatches.push(Mismatch {
+                    day: self.day,
+                    part: *part,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        Ok(mismatches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = parse_expected("# part answer\n1 7\n\n2 336\n").unwrap();
+
+        assert_eq!(expected, vec![(1, "7".to_string()), (2, "336".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_expected_invalid() {
+        assert!(parse_expected("3 7").is_err());
+        assert!(parse_expected("1").is_err());
+    }
+
+    #[test]
+    fn test_day_of_dir() {
+        assert_eq!(day_of_dir(Path::new("examples/day03")), Some(3));
+        assert_eq!(day_of_dir(Path::new("examples/other")), None);
+    }
+
+    #[test]
+    fn test_check_mismatch() {
+        let example = Example {
+            day: 3,
+            name: "example".to_string(),
+            path: examples_dir().join("day03/example.txt"),
+            expected: vec![(2, "335".to_string())],
+        };
+
+        assert_eq!(
+            example.check().unwrap(),
+            vec![Mismatch {
+                day: 3,
+                part: 2,
+                expected: "335".to_string(),
+                actual: "336".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::bench::BenchResult;
+use crate::input::input_dir;
+use crate::{fmt_dur, Error, Result};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const HEADER: &str = "# run\tname\tcommit\trustc\tcpu\tday\tpart\tmedian_ns";
+
+/// Parts slower than this many percent are flagged by default.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Where and with what a benchmark ran, fields are `None` when unknown.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Environment {
+    pub commit: Option<String>,
+    pub rustc: Option<String>,
+    pub cpu: Option<String>,
+}
+
+/// The median time of a part in one benchmark run.
+///
+/// Records of the same run share `run`, the milliseconds since the epoch when
+/// it was recorded, and optionally a `name` to compare against later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub run: u64,
+    pub name: Option<String>,
+    pub env: Environment,
+    pub day: u8,
+    pub part: u8,
+    pub median: Duration,
+}
+
+/// All recorded benchmark runs, oldest first.
+///
+/// The history file has one tab separated line per record, with `-` for
+/// unknown fields. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct History(pub Vec<Record>);
+
+/// A part that ran in both the baseline and the latest run.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub day: u8,
+    pub part: u8,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+/// `bench_history.tsv`, next to the input directory.
+pub fn history_path() -> PathBuf {
+    input_dir().with_file_name("bench_history.tsv")
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(output.stdout).ok()?;
+    Some(out.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok();
+    let model = cpuinfo.as_deref().and_then(|info| {
+        info.lines()
+            .find(|line| line.starts_with("model name"))
+            .and_then(|line| line.split(':').nth(1))
+            .map(|model| model.trim().to_string())
+    });
+    model.or_else(|| command_output("sysctl", &["-n", "machdep.cpu.brand_string"]))
+}
+
+impl Environment {
+    /// Looks up the current commit, rustc version and CPU model.
+    pub fn detect() -> Environment {
+        let root = env!("CARGO_MANIFEST_DIR");
+        Environment {
+            commit: command_output("git", &["-C", root, "rev-parse", "--short", "HEAD"]),
+            rustc: command_output("rustc", &["--version"]),
+            cpu: cpu_model(),
+        }
+    }
+}
+
+impl Record {
+    /// Records the median of each part benchmarked in `results`.
+    pub fn from_bench(
+        results: &[BenchResult],
+        name: Option<&str>,
+        env: &Environment,
+    ) -> Vec<Record> {
+        let run = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        results
+            .iter()
+            .flat_map(|result| {
+                result.parts.iter().map(move |(part, stats)| Record {
+                    run,
+                    name: name.map(String::from),
+                    env: env.clone(),
+                    day: result.day,
+                    part: *part,
+                    median: stats.median,
+                })
+            })
+            .collect()
+    }
+}
+
+fn field(value: &Option<String>) -> String {
+    match value {
+        Some(value) => value.replace(['\t', '\n'], " "),
+        None => "-".to_string(),
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.run,
+            field(&self.name),
+            field(&self.env.commit),
+            field(&self.env.rustc),
+            field(&self.env.cpu),
+            self.day,
+            self.part,
+            self.median.as_nanos()
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Record, String> {
+        let fields: Vec<&str> = s.split('\t').collect();
+        if fields.len() != 8 {
+            return Err(format!("expected 8 fields, got {}", fields.len()));
+        }
+        let text = |s: &str| match s {
+            "-" => None,
+            s => Some(s.to_string()),
+        };
+        let number = |s: &str| s.parse::<u64>().map_err(|e| format!("{:?}: {}", s, e));
+
+        Ok(Record {
+            run: number(fields[0])?,
+            name: text(fields[1]),
+            env: Environment {
+                commit: text(fields[2]),
+                rustc: text(fields[3]),
+                cpu: text(fields[4]),
+            },
+            day: fields[5].parse().map_err(|_| "invalid day")?,
+            part: fields[6].parse().map_err(|_| "invalid part")?,
+            median: Duration::from_nanos(number(fields[7])?),
+        })
+    }
+}
+
+impl FromStr for History {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<History> {
+        let mut records = Vec::new();
+
+        for (n, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            records.push(line.parse().map_err(|e| Error::parse(n + 1, e))?);
+        }
+
+        Ok(History(records))
+    }
+}
+
+impl History {
+    /// Loads the history at `path`, which is empty when there is no file yet.
+    pub fn load(path: &Path) -> Result<History> {
+        match fs::read_to_string(path) {
+            Ok(content) => content.parse(),
+            Err(cause) if cause.kind() == std::io::ErrorKind::NotFound => Ok(History::default()),
+            Err(cause) => Err(Error::io(path, cause)),
+        }
+    }
+
+    /// Appends `records` to the history file at `path`, creating it if needed.
+    pub fn append(path: &Path, records: &[Record]) -> Result<()> {
+        let new = !path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|cause| Error::io(path, cause))?;
+
+        let mut out = String::new();
+        if new {
+            out += HEADER;
+            out += "\n";
+        }
+        for record in records {
+            out += &format!("{}\n", record);
+        }
+        file.write_all(out.as_bytes())
+            .map_err(|cause| Error::io(path, cause))
+    }
+
+    /// The ids of all runs, oldest first.
+    fn runs(&self) -> Vec<u64> {
+        let mut runs: Vec<u64> = self.0.iter().map(|r| r.run).collect();
+        runs.dedup();
+        runs
+    }
+
+    fn run(&self, run: u64) -> Vec<&Record> {
+        self.0.iter().filter(|r| r.run == run).collect()
+    }
+
+    /// The baseline and the latest run. The baseline is the run before the
+    /// latest one, or the latest earlier run called `baseline`.
+    pub fn latest_pair(&self, baseline: Option<&str>) -> Result<(Vec<&Record>, Vec<&Record>)> {
+        let runs = self.runs();
+        let (latest, earlier) = runs
+            .split_last()
+            .ok_or_else(|| Error::Other("no benchmark runs recorded yet".to_string()))?;
+
+        let base = match baseline {
+            Some(name) => earlier.iter().rev().find(|run| {
+                self.run(**run)
+                    .first()
+                    .is_some_and(|r| r.name.as_deref() == Some(name))
+            }),
+            None => earlier.last(),
+        };
+        let base = base.ok_or_else(|| match baseline {
+            Some(name) => Error::Other(format!("no earlier run named {:?}", name)),
+            None => Error::Other("only one benchmark run recorded".to_string()),
+        })?;
+
+        Ok((self.run(*base), self.run(*latest)))
+    }
+}
+
+/// Pairs up the parts that ran in both `before` and `after`.
+pub fn compare(before: &[&Record], after: &[&Record]) -> Vec<Change> {
+    after
+        .iter()
+        .filter_map(|a| {
+            let b = before.iter().find(|b| b.day == a.day && b.part == a.part)?;
+            Some(Change {
+                day: a.day,
+                part: a.part,
+                before: b.median,
+                after: a.median,
+            })
+        })
+        .collect()
+}
+
+impl Change {
+    /// How much slower the part got, in percent. Negative when it got faster.
+    pub fn percent(&self) -> f64 {
+        let before = self.before.as_nanos() as f64;
+        if before == 0.0 {
+            return 0.0;
+        }
+        (self.after.as_nanos() as f64 - before) / before * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent() > threshold
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {:02} part {}: {} -> {} ({:+.1}%)",
+            self.day,
+            self.part,
+            fmt_dur(self.before),
+            fmt_dur(self.after),
+            self.percent()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(run: u64, name: Option<&str>, part: u8, ms: u64) -> Record {
+        Record {
+            run,
+            name: name.map(String::from),
+            env: Environment {
+                commit: Some("abc1234".to_string()),
+                rustc: None,
+                cpu: Some("Some\tCPU".to_string()),
+            },
+            day: 1,
+            part,
+            median: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rec = record(5, Some("base"), 2, 3);
+        let line = rec.to_string();
+        let parsed: Record = line.parse().unwrap();
+
+        assert_eq!(line, "5\tbase\tabc1234\t-\tSome CPU\t1\t2\t3000000");
+        assert_eq!(parsed.env.cpu.as_deref(), Some("Some CPU"));
+        assert_eq!(parsed.median, rec.median);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("# header\n1\t-\t-\t-\t-\t1\t1\t5\n"
+            .parse::<History>()
+            .is_ok());
+        assert!("1\t-\t-\t-\t-\t1\t1".parse::<History>().is_err());
+        assert!("1\t-\t-\t-\t-\t1\tx\t5".parse::<History>().is_err());
+    }
+
+    #[test]
+    fn test_latest_pair() {
+        let history = History(vec![
+            record(1, Some("base"), 1, 10),
+            record(2, None, 1, 12),
+            record(3, None, 1, 20),
+        ]);
+
+        let (before, after) = history.latest_pair(None).unwrap();
+        assert_eq!((before[0].run, after[0].run), (2, 3));
+
+        let (before, _) = history.latest_pair(Some("base")).unwrap();
+        assert_eq!(before[0].run, 1);
+
+        assert!(history.latest_pair(Some("other")).is_err());
+        assert!(History(vec![record(1, None, 1, 1)])
+            .latest_pair(None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let before = [record(1, None, 1, 10), record(1, None, 2, 10)];
+        let after = [record(2, None, 1, 12), record(2, None, 2, 10)];
+        let changes = compare(
+            &before.iter().collect::<Vec<_>>(),
+            &after.iter().collect::<Vec<_>>(),
+        );
+
+        assert_eq!(changes.len(), 2);
+        assert!((changes[0].percent() - 20.0).abs() < 1e-9);
+        assert!(changes[0].is_regression(DEFAULT_THRESHOLD));
+        assert!(!changes[1].is_regression(DEFAULT_THRESHOLD));
+        assert_eq!(
+            changes[0].to_string(),
+            "Day 01 part 1: 10ms -> 12ms (+20.0%)"
+        );
+    }
+
+    #[test]
+    fn test_append_load() {
+        let path = std::env::temp_dir().join(format!("aoc-history-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        History::append(&path, &[record(1, None, 1, 10)]).unwrap();
+        History::append(&path, &[record(2, None, 1, 11)]).unwrap();
+        let history = History::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(history.0.len(), 2);
+        assert_eq!(history.runs(), vec![1, 2]);
+    }
+}
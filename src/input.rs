@@ -0,0 +1,85 @@
+use crate::{Error, Result};
+use log::debug;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the directory the `dayNN.txt` inputs are
+/// read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Where to read the puzzle input of a day from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// `dayNN.txt` in the input directory, see `input_dir`.
+    Default,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets a command line argument, where `-` means stdin.
+    pub fn from_arg(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::Path(PathBuf::from(arg))
+        }
+    }
+
+    /// Reads the input of `day` from this source.
+    pub fn read(&self, day: u8) -> Result<String> {
+        match self {
+            Source::Default => read_file(&default_path(day)),
+            Source::Path(path) => read_file(path),
+            Source::Stdin => {
+                debug!("reading stdin");
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// The directory holding the inputs: `AOC_INPUT_DIR` if set, otherwise
+/// `inputs/` in the crate root.
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"),
+    }
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    input_dir().join(format!("day{:02}.txt", day))
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    debug!("reading {}", path.display());
+    fs::read_to_string(path).map_err(|cause| Error::io(path, cause))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("inputs/day01.txt"),
+            Source::Path(PathBuf::from("inputs/day01.txt"))
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let source = Source::Path(PathBuf::from("does/not/exist.txt"));
+        let err = source.read(1).unwrap_err();
+
+        assert!(matches!(err, Error::Io { .. }));
+        assert!(err.to_string().starts_with("does/not/exist.txt: "));
+    }
+}
@@ -1,25 +1,506 @@
+use duration::HumanDuration;
+use input::Source;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Duration;
+use timer::{Phase, Timer};
 
-pub fn fmt_time(ms: f64) -> String {
-    if ms <= 1.0 {
-        let micro_sec = ms * 1000.0;
-        return format!("{}µs", micro_sec.round());
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod download;
+pub mod duration;
+pub mod error;
+pub mod examples;
+pub mod history;
+pub mod input;
+pub mod report;
+pub mod scaffold;
+pub mod timer;
+pub mod watch;
+
+pub use error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Answer {
+        Answer::Number(n)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Number(n.into())
     }
-    if ms < 1000.0 {
-        let whole_ms = ms.floor();
-        let rem_ms = ms - whole_ms;
-        return format!("{}ms ", whole_ms) + &fmt_time(rem_ms);
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Answer {
+        Answer::Number(n.into())
     }
-    let sec: f64 = ms / 1000.0;
-    if sec < 60.0 {
-        let whole_sec = sec.floor();
-        let rem_ms = ms - whole_sec * 1000.0;
-        return format!("{}s ", whole_sec) + &fmt_time(rem_ms);
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::Number(n.into())
     }
-    let min: f64 = sec / 60.0;
-    format!("{}m ", min.floor()) + &fmt_time((sec % 60.0) * 1000.0)
 }
 
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Number(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+/// A puzzle solution split into a parsing stage and the two parts working on
+/// the parsed input.
+///
+/// The input is shared with the threads the parts run on, so it has to be
+/// `Send` and `Sync`.
+pub trait Solver {
+    type Input: Send + Sync;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// Parsed input of a solver with the concrete `Input` type erased, so days can
+/// be stored side by side in `DAYS`.
+pub trait Parsed: Send + Sync {
+    fn part1(&self) -> Result<Answer>;
+    fn part2(&self) -> Result<Answer>;
+
+    /// Solves part 1 or 2.
+    fn solve(&self, part: u8) -> Result<Answer> {
+        match part {
+            1 => self.part1(),
+            2 => self.part2(),
+            _ => Err(Error::Other(format!("no part {}", part))),
+        }
+    }
+}
+
+struct ParsedInput<S: Solver>(S::Input);
+
+impl<S: Solver> Parsed for ParsedInput<S> {
+    fn part1(&self) -> Result<Answer> {
+        S::part1(&self.0)
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        S::part2(&self.0)
+    }
+}
+
+fn parse_shared<S: Solver + 'static>(input: &str) -> Result<Arc<dyn Parsed>> {
+    Ok(Arc::new(ParsedInput::<S>(S::parse(input)?)))
+}
+
+/// Time a part may take before it is reported as timed out, unless the day or
+/// the run sets another deadline.
+pub const DEFAULT_DEADLINE: Duration = Duration::from_secs(15);
+
+/// A registered day, linking its number to its solver.
+pub struct Day {
+    pub day: u8,
+    pub parse: fn(&str) -> Result<Arc<dyn Parsed>>,
+    /// Deadline of each part, `DEFAULT_DEADLINE` when `None`.
+    pub deadline: Option<Duration>,
+}
+
+impl Day {
+    pub const fn new<S: Solver + 'static>(day: u8) -> Day {
+        Day {
+            day,
+            parse: parse_shared::<S>,
+            deadline: None,
+        }
+    }
+
+    /// Gives the parts of this day `deadline` instead of `DEFAULT_DEADLINE`.
+    pub const fn with_deadline(self, deadline: Duration) -> Day {
+        Day {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+}
+
+/// All days with a solution, in order.
+pub const DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1),
+    Day::new::<day02::Day02>(2),
+    Day::new::<day03::Day03>(3),
+    Day::new::<day04::Day04>(4),
+];
+
+pub fn get_day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// What solving a part came to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Answer(Answer),
+    /// The part failed, with the message of its error.
+    Error(String),
+    /// The part missed its deadline.
+    Timeout,
+}
+
+pub struct PartResult {
+    pub part: u8,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+impl PartResult {
+    pub fn answer(&self) -> Option<&Answer> {
+        match &self.outcome {
+            Outcome::Answer(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    /// The answer as shown in reports, `ERROR` with the message of a failed
+    /// part and `TIMEOUT` for a timed out one.
+    pub fn answer_text(&self) -> String {
+        match &self.outcome {
+            Outcome::Answer(answer) => answer.to_string(),
+            Outcome::Error(message) => format!("ERROR: {}", message),
+            Outcome::Timeout => "TIMEOUT".to_string(),
+        }
+    }
+}
+
+pub struct DayResult {
+    pub day: u8,
+    pub parse_duration: Duration,
+    pub parts: Vec<PartResult>,
+    /// Timings of the reading, parsing and solving phases, including any
+    /// spans the solver opened.
+    pub phases: Vec<Phase>,
+}
+
+impl DayResult {
+    /// The result of a day that failed before solving, with each part it was
+    /// run for failed with the error.
+    pub fn failed(day: u8, part: Option<u8>, err: &Error) -> DayResult {
+        let message = match err {
+            Error::Day { cause, .. } => cause.to_string(),
+            err => err.to_string(),
+        };
+        let parts = (1..=2).filter(|n| part.is_none_or(|p| p == *n));
+        DayResult {
+            day,
+            parse_duration: Duration::default(),
+            parts: parts
+                .map(|n| PartResult {
+                    part: n,
+                    outcome: Outcome::Error(message.clone()),
+                    duration: Duration::default(),
+                })
+                .collect(),
+            phases: Vec::new(),
+        }
+    }
+
+    /// Time spent parsing and solving, counting timed out parts as their
+    /// deadline.
+    pub fn total_duration(&self) -> Duration {
+        self.parse_duration + self.parts.iter().map(|p| p.duration).sum::<Duration>()
+    }
+}
+
+/// Options of `run_day`, the defaults run both parts with the deadline of the
+/// day.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    pub part: Option<u8>,
+    /// Overrides the deadline of every day.
+    pub deadline: Option<Duration>,
+}
+
+/// Reads the input of `day` from `source`, then parses and solves it, timing
+/// each stage. Runs both parts unless a part is given.
+///
+/// Each part runs on its own thread and is given up on, and left running,
+/// when it misses its deadline. A part that fails or times out does not fail
+/// the day, its outcome is kept in its `PartResult`. The timer is installed
+/// while the day runs, so solvers can time their own phases with `timer::span`.
+pub fn run_day(day: &Day, source: &Source, options: RunOptions) -> Result<DayResult> {
+    let timer = Timer::new();
+    timer.install();
+    let result = run_timed(&timer, day, source, options);
+    Timer::uninstall();
+
+    let mut result = result.map_err(|e| e.in_day(day.day))?;
+    result.phases = timer.phases();
+    Ok(result)
+}
+
+/// Parts being solved on their own thread, see `parts_solving`.
+static SOLVING: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a part in `SOLVING` until dropped.
+struct Solving;
+
+impl Solving {
+    fn start() -> Solving {
+        SOLVING.fetch_add(1, Ordering::SeqCst);
+        Solving
+    }
+}
+
+impl Drop for Solving {
+    fn drop(&mut self) {
+        SOLVING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The number of parts still being solved, on any thread. Once `run_day`
+/// returns, the parts of its day counted here are the ones that timed out.
+pub fn parts_solving() -> usize {
+    SOLVING.load(Ordering::SeqCst)
+}
+
+/// Solves `part` on a new thread, giving up on it when it takes longer than
+/// `deadline`. The outcome comes with the time it took and the phases timed on
+/// that thread.
+pub(crate) fn solve_with_deadline(
+    parsed: &Arc<dyn Parsed>,
+    part: u8,
+    deadline: Duration,
+) -> Result<(Outcome, Duration, Vec<Phase>)> {
+    let (tx, rx) = mpsc::channel();
+    let parsed = Arc::clone(parsed);
+    let solving = Solving::start();
+    thread::Builder::new()
+        .name(format!("part {}", part))
+        .spawn(move || {
+            let _solving = solving;
+            let timer = Timer::new();
+            timer.install();
+            let span = timer.span(&format!("part {}", part));
+            let answer = parsed.solve(part);
+            let dur = span.finish();
+            Timer::uninstall();
+            let outcome = match answer {
+                Ok(answer) => Outcome::Answer(answer),
+                Err(err) => Outcome::Error(err.to_string()),
+            };
+            let _ = tx.send((outcome, dur, timer.phases()));
+        })?;
+
+    match rx.recv_timeout(deadline) {
+        Ok(solved) => Ok(solved),
+        Err(RecvTimeoutError::Timeout) => Ok((Outcome::Timeout, deadline, Vec::new())),
+        Err(RecvTimeoutError::Disconnected) => Ok((
+            Outcome::Error("panicked".to_string()),
+            Duration::default(),
+            Vec::new(),
+        )),
+    }
+}
+
+fn run_timed(timer: &Timer, day: &Day, source: &Source, options: RunOptions) -> Result<DayResult> {
+    let input = {
+        let _t = timer.span("read");
+        source.read(day.day)?
+    };
+
+    let span = timer.span("parse");
+    let parsed = (day.parse)(&input)?;
+    let parse_dur = span.finish();
+
+    let deadline = options
+        .deadline
+        .or(day.deadline)
+        .unwrap_or(DEFAULT_DEADLINE);
+    let mut parts = Vec::new();
+
+    for n in 1..=2 {
+        if options.part.is_some_and(|p| p != n) {
+            continue;
+        }
+
+        let (outcome, duration, phases) = solve_with_deadline(&parsed, n, deadline)?;
+        timer.graft(&phases);
+        parts.push(PartResult {
+            part: n,
+            outcome,
+            duration,
+        });
+    }
+
+    Ok(DayResult {
+        day: day.day,
+        parse_duration: parse_dur,
+        parts,
+        phases: Vec::new(),
+    })
+}
+
+/// Runs `days` concurrently on a pool of one thread per CPU, returning the
+/// results in the order of `days`.
+pub fn run_days(days: &[&Day], source: &Source, options: RunOptions) -> Vec<Result<DayResult>> {
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(days.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<DayResult>>>> =
+        Mutex::new(days.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let day = match days.get(i) {
+                    Some(day) => day,
+                    None => break,
+                };
+                let result = run_day(day, source, options);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every day is run"))
+        .collect()
+}
+
+/// Formats `dur` as its two largest units, see `HumanDuration`.
 pub fn fmt_dur(dur: Duration) -> String {
-    fmt_time(dur.as_secs_f64() * 1000.0)
+    HumanDuration(dur).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    struct Slow;
+
+    impl Solver for Slow {
+        type Input = ();
+
+        fn parse(_: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn part1(_: &()) -> Result<Answer> {
+            Ok(1.into())
+        }
+
+        fn part2(_: &()) -> Result<Answer> {
+            thread::sleep(Duration::from_secs(1));
+            Ok(2.into())
+        }
+    }
+
+    struct Failing;
+
+    impl Solver for Failing {
+        type Input = ();
+
+        fn parse(_: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn part1(_: &()) -> Result<Answer> {
+            Err(Error::NoSolution)
+        }
+
+        fn part2(_: &()) -> Result<Answer> {
+            panic!("part 2 of Failing")
+        }
+    }
+
+    fn example() -> Source {
+        Source::Path(Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/day01/example.txt"))
+    }
+
+    #[test]
+    fn test_deadline() {
+        let day = Day::new::<Slow>(1).with_deadline(Duration::from_millis(20));
+        let result = run_day(&day, &example(), RunOptions::default()).unwrap();
+
+        assert_eq!(result.parts[0].outcome, Outcome::Answer(Answer::Number(1)));
+        assert_eq!(result.parts[1].outcome, Outcome::Timeout);
+        assert_eq!(result.parts[1].answer_text(), "TIMEOUT");
+        assert_eq!(result.parts[1].duration, Duration::from_millis(20));
+        assert!(parts_solving() >= 1);
+    }
+
+    #[test]
+    fn test_deadline_override() {
+        let day = Day::new::<Slow>(1);
+        let options = RunOptions {
+            part: Some(2),
+            deadline: Some(Duration::from_millis(10)),
+        };
+        let result = run_day(&day, &example(), options).unwrap();
+
+        assert_eq!(result.parts.len(), 1);
+        assert_eq!(result.parts[0].outcome, Outcome::Timeout);
+    }
+
+    #[test]
+    fn test_failed_day() {
+        let err = Error::parse(3, "invalid digit").in_day(2);
+        let result = DayResult::failed(2, Some(1), &err);
+
+        assert_eq!(result.parts.len(), 1);
+        assert_eq!(
+            result.parts[0].answer_text(),
+            "ERROR: line 3: invalid digit"
+        );
+    }
+
+    #[test]
+    fn test_failing_parts() {
+        let result = run_day(&Day::new::<Failing>(1), &example(), RunOptions::default()).unwrap();
+
+        assert_eq!(
+            result.parts[0].outcome,
+            Outcome::Error("no solution found".to_string())
+        );
+        assert_eq!(result.parts[0].answer_text(), "ERROR: no solution found");
+        assert_eq!(
+            result.parts[1].outcome,
+            Outcome::Error("panicked".to_string())
+        );
+    }
 }
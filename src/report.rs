@@ -0,0 +1,269 @@
+use crate::{fmt_dur, Answer, DayResult, Outcome, PartResult};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Output format of the answers and timings of a run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    /// One aligned table of all days with a grand total.
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+impl Format {
+    /// Writes one record per solved part, or per day for `Text`.
+    pub fn write<W: Write>(self, w: &mut W, results: &[DayResult]) -> io::Result<()> {
+        match self {
+            Format::Text => write_text(w, results),
+            Format::Table => write_table(w, results),
+            Format::Json => write_json(w, results),
+            Format::Csv => write_csv(w, results),
+        }
+    }
+}
+
+fn write_text<W: Write>(w: &mut W, results: &[DayResult]) -> io::Result<()> {
+    for result in results {
+        writeln!(w, "Day {:02}", result.day)?;
+        writeln!(w, "Parsing input")?;
+        writeln!(w, "Took {}", fmt_dur(result.parse_duration))?;
+        for part in result.parts.iter() {
+            writeln!(w, "Part {}: {}", part.part, part.answer_text())?;
+            writeln!(w, "Took {}", fmt_dur(part.duration))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_table<W: Write>(w: &mut W, results: &[DayResult]) -> io::Result<()> {
+    let mut rows = vec![[
+        "Day".to_string(),
+        "Part".to_string(),
+        "Answer".to_string(),
+        "Time".to_string(),
+    ]];
+    let mut total = Duration::default();
+    for r in results {
+        rows.push([
+            format!("{:02}", r.day),
+            "parse".to_string(),
+            String::new(),
+            fmt_dur(r.parse_duration),
+        ]);
+        total += r.parse_duration;
+        for p in r.parts.iter() {
+            rows.push([
+                format!("{:02}", r.day),
+                p.part.to_string(),
+                p.answer_text(),
+                fmt_dur(p.duration),
+            ]);
+            total += p.duration;
+        }
+    }
+    rows.push([
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        fmt_dur(total),
+    ]);
+
+    let mut widths = [0; 4];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in rows.iter() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(w, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// The answer and the error of a part as JSON, one of them `null`. The error
+/// of a timed out part is `"timeout"`.
+fn json_outcome(part: &PartResult) -> (String, String) {
+    let null = || "null".to_string();
+    match &part.outcome {
+        Outcome::Answer(Answer::Number(n)) => (n.to_string(), null()),
+        Outcome::Answer(Answer::Text(s)) => (json_string(s), null()),
+        Outcome::Error(message) => (null(), json_string(message)),
+        Outcome::Timeout => (null(), json_string("timeout")),
+    }
+}
+
+fn write_json<W: Write>(w: &mut W, results: &[DayResult]) -> io::Result<()> {
+    let records: Vec<String> = results
+        .iter()
+        .flat_map(|r| {
+            r.parts.iter().map(move |p| {
+                let (answer, error) = json_outcome(p);
+                format!(
+                    "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"error\": {}, \"parse_ns\": {}, \"solve_ns\": {}}}",
+                    r.day,
+                    p.part,
+                    answer,
+                    error,
+                    r.parse_duration.as_nanos(),
+                    p.duration.as_nanos()
+                )
+            })
+        })
+        .collect();
+
+    if records.is_empty() {
+        return writeln!(w, "[]");
+    }
+    writeln!(w, "[\n{}\n]", records.join(",\n"))
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_csv<W: Write>(w: &mut W, results: &[DayResult]) -> io::Result<()> {
+    writeln!(w, "day,part,answer,parse_ns,solve_ns")?;
+    for r in results {
+        for p in r.parts.iter() {
+            writeln!(
+                w,
+                "{},{},{},{},{}",
+                r.day,
+                p.part,
+                csv_field(&p.answer_text()),
+                r.parse_duration.as_nanos(),
+                p.duration.as_nanos()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<DayResult> {
+        vec![DayResult {
+            day: 1,
+            parse_duration: Duration::from_nanos(1500),
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    outcome: Outcome::Answer(Answer::Number(514579)),
+                    duration: Duration::from_nanos(200),
+                },
+                PartResult {
+                    part: 2,
+                    outcome: Outcome::Answer(Answer::Text("a \"b\", c".to_string())),
+                    duration: Duration::from_nanos(300),
+                },
+            ],
+            phases: Vec::new(),
+        }]
+    }
+
+    fn render(format: Format, results: &[DayResult]) -> String {
+        let mut out = Vec::new();
+        format.write(&mut out, results).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let expected = r#"[
+  {"day": 1, "part": 1, "answer": 514579, "error": null, "parse_ns": 1500, "solve_ns": 200},
+  {"day": 1, "part": 2, "answer": "a \"b\", c", "error": null, "parse_ns": 1500, "solve_ns": 300}
+]
+"#;
+
+        assert_eq!(render(Format::Json, &results()), expected);
+        assert_eq!(render(Format::Json, &[]), "[]\n");
+    }
+
+    #[test]
+    fn test_failed_parts() {
+        let mut results = results();
+        results[0].parts[0].outcome = Outcome::Error("no solution found".to_string());
+        results[0].parts[1].outcome = Outcome::Timeout;
+
+        let json = render(Format::Json, &results);
+        assert!(json.contains(r#""answer": null, "error": "no solution found""#));
+        assert!(json.contains(r#""answer": null, "error": "timeout""#));
+        assert!(render(Format::Csv, &results).contains("\n1,1,ERROR: no solution found,"));
+        assert!(render(Format::Text, &results).contains("Part 1: ERROR: no solution found\n"));
+        assert!(render(Format::Table, &results).contains("01     2      TIMEOUT"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let expected = "day,part,answer,parse_ns,solve_ns
+1,1,514579,1500,200
+1,2,\"a \"\"b\"\", c\",1500,300
+";
+
+        assert_eq!(render(Format::Csv, &results()), expected);
+    }
+
+    #[test]
+    fn test_table() {
+        let expected = "Day    Part   Answer    Time
+01     parse            1µs 500ns
+01     1      514579    200ns
+01     2      a \"b\", c  300ns
+Total                   2µs
+";
+
+        assert_eq!(render(Format::Table, &results()), expected);
+    }
+}
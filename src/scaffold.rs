@@ -0,0 +1,189 @@
+use crate::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::{Answer, Error, Result, Solver};
+
+/// Day {day}.
+pub struct Day{nn};
+
+impl Solver for Day{nn} {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(_lines: &Vec<String>) -> Result<Answer> {
+        Err(Error::NoSolution)
+    }
+
+    fn part2(_lines: &Vec<String>) -> Result<Answer> {
+        Err(Error::NoSolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_part1() {
+        let input = Day{nn}::parse(EXAMPLE).unwrap();
+
+        assert!(matches!(Day{nn}::part1(&input), Err(Error::NoSolution)));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = Day{nn}::parse(EXAMPLE).unwrap();
+
+        assert!(matches!(Day{nn}::part2(&input), Err(Error::NoSolution)));
+    }
+}
+"#;
+
+/// The solver module of `day`, rendered from the template.
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{nn}", &format!("{:02}", day))
+}
+
+/// Adds `pub mod dayNN;` and the `DAYS` entry for `day` to the source of
+/// `lib.rs`, keeping both lists ordered by day.
+pub fn register(lib: &str, day: u8) -> Result<String> {
+    let nn = format!("{:02}", day);
+    let module = format!("pub mod day{};", nn);
+    let entry = format!("    Day::new::<day{0}::Day{0}>({1}),", nn, day);
+    if lib.lines().any(|l| l == module) {
+        return Err(Error::Other(format!("day {} is already registered", day)));
+    }
+
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+    let mod_pos =
+        insert_position(&lines, "pub mod day", &module).ok_or("no day modules found in lib.rs")?;
+    lines.insert(mod_pos, module);
+    let entry_pos = insert_position(&lines, "    Day::new::<day", &entry)
+        .ok_or("no DAYS entries found in lib.rs")?;
+    lines.insert(entry_pos, entry);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+// Index right after the last line starting with `prefix` that sorts before
+// `line`, so zero-padded day numbers stay in order.
+fn insert_position(lines: &[String], prefix: &str, line: &str) -> Option<usize> {
+    let first = lines.iter().position(|l| l.starts_with(prefix))?;
+    let after = lines[first..]
+        .iter()
+        .take_while(|l| l.starts_with(prefix) && l.as_str() < line)
+        .count();
+    Some(first + after)
+}
+
+/// Creates the solver module and an empty input for `day` under the crate
+/// root `root` and registers the day in `lib.rs`. Refuses to overwrite an
+/// existing day. Returns the created files.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let module_path = root.join(format!("src/day{:02}.rs", day));
+    let input_path = root.join(format!("inputs/day{:02}.txt", day));
+    let lib_path = root.join("src/lib.rs");
+
+    if module_path.exists() {
+        return Err(Error::Other(format!(
+            "{} already exists",
+            module_path.display()
+        )));
+    }
+
+    let lib = fs::read_to_string(&lib_path).map_err(|cause| Error::io(&lib_path, cause))?;
+    let lib = register(&lib, day)?;
+
+    fs::write(&module_path, render(day)).map_err(|cause| Error::io(&module_path, cause))?;
+    fs::write(&lib_path, lib).map_err(|cause| Error::io(&lib_path, cause))?;
+    let mut created = vec![module_path];
+    if !input_path.exists() {
+        fs::create_dir_all(root.join("inputs"))?;
+        fs::write(&input_path, "").map_err(|cause| Error::io(&input_path, cause))?;
+        created.push(input_path);
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const LIB: &str = "pub mod day01;
+pub mod day03;
+pub mod input;
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1),
+    Day::new::<day03::Day03>(3),
+];
+";
+
+    #[test]
+    fn test_render() {
+        let module = render(7);
+
+        assert!(module.contains("/// Day 7.\npub struct Day07;"));
+        assert!(module.contains("impl Solver for Day07 {"));
+        assert!(!module.contains("{nn}"));
+    }
+
+    #[test]
+    fn test_register_between() {
+        let expected = "pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod input;
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1),
+    Day::new::<day02::Day02>(2),
+    Day::new::<day03::Day03>(3),
+];
+";
+
+        assert_eq!(register(LIB, 2).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_register_last() {
+        let lib = register(LIB, 12).unwrap();
+
+        assert!(lib.contains("pub mod day03;\npub mod day12;\npub mod input;"));
+        assert!(lib.contains("(3),\n    Day::new::<day12::Day12>(12),\n];"));
+    }
+
+    #[test]
+    fn test_register_existing() {
+        assert!(register(LIB, 3).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        let created = new_day(&root, 5).unwrap();
+        let again = new_day(&root, 5);
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            created,
+            vec![root.join("src/day05.rs"), root.join("inputs/day05.txt")]
+        );
+        assert!(again.is_err());
+        assert!(lib.contains("Day::new::<day05::Day05>(5),"));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::alloc::{self, AllocStats};
+use crate::fmt_dur;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Timer>> = const { RefCell::new(None) };
+}
+
+struct Node {
+    name: String,
+    parent: Option<usize>,
+    duration: Duration,
+    count: usize,
+    alloc: AllocStats,
+}
+
+#[derive(Default)]
+struct Tree {
+    nodes: Vec<Node>,
+    open: Vec<usize>,
+}
+
+/// Records the time spent in nested, named phases.
+///
+/// A span opened while another one is open becomes its child, and spans with
+/// the same name under the same parent are added up:
+///
+/// ```
+/// use advent_of_code::timer::Timer;
+///
+/// let timer = Timer::new();
+/// {
+///     let _t = timer.span("part 2");
+///     for _ in 0..3 {
+///         let _t = timer.span("route");
+///     }
+/// }
+/// let phases = timer.phases();
+/// assert_eq!(phases[0].children[0].count, 3);
+/// ```
+#[derive(Clone, Default)]
+pub struct Timer(Rc<RefCell<Tree>>);
+
+/// A running phase, recorded when dropped or finished.
+#[must_use = "the phase ends when the span is dropped"]
+pub struct Span {
+    timer: Option<Timer>,
+    index: usize,
+    mark: Option<alloc::Mark>,
+    start: Instant,
+}
+
+/// The recorded time of a phase and its nested phases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub name: String,
+    pub duration: Duration,
+    pub count: usize,
+    /// Allocations made in the phase, when built with `alloc-stats`.
+    pub alloc: Option<AllocStats>,
+    pub children: Vec<Phase>,
+}
+
+impl Timer {
+    pub fn new() -> Timer {
+        Timer::default()
+    }
+
+    pub fn span(&self, name: &str) -> Span {
+        let mut tree = self.0.borrow_mut();
+        let parent = tree.open.last().copied();
+        let index = tree.child(parent, name);
+        tree.open.push(index);
+
+        Span {
+            timer: Some(self.clone()),
+            index,
+            mark: Some(alloc::start()),
+            start: Instant::now(),
+        }
+    }
+
+    /// Adds phases recorded by another timer, like one on another thread,
+    /// below the innermost open span.
+    pub fn graft(&self, phases: &[Phase]) {
+        let mut tree = self.0.borrow_mut();
+        let parent = tree.open.last().copied();
+        tree.graft(parent, phases);
+    }
+
+    /// Makes this the timer `span` records to on the current thread, until
+    /// `uninstall` is called.
+    pub fn install(&self) {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+    }
+
+    pub fn uninstall() {
+        CURRENT.with(|current| *current.borrow_mut() = None);
+    }
+
+    /// The recorded phases, in the order they were first opened.
+    pub fn phases(&self) -> Vec<Phase> {
+        let tree = self.0.borrow();
+        phases_of(&tree, None)
+    }
+}
+
+impl Tree {
+    /// The node called `name` below `parent`, added if there is none yet.
+    fn child(&mut self, parent: Option<usize>, name: &str) -> usize {
+        let found = self
+            .nodes
+            .iter()
+            .position(|n| n.parent == parent && n.name == name);
+        found.unwrap_or_else(|| {
+            self.nodes.push(Node {
+                name: name.to_string(),
+                parent,
+                duration: Duration::default(),
+                count: 0,
+                alloc: AllocStats::default(),
+            });
+            self.nodes.len() - 1
+        })
+    }
+
+    fn graft(&mut self, parent: Option<usize>, phases: &[Phase]) {
+        for phase in phases {
+            let index = self.child(parent, &phase.name);
+            let node = &mut self.nodes[index];
+            node.duration += phase.duration;
+            node.count += phase.count;
+            if let Some(alloc) = phase.alloc {
+                node.alloc.merge(alloc);
+            }
+            self.graft(Some(index), &phase.children);
+        }
+    }
+}
+
+fn phases_of(tree: &Tree, parent: Option<usize>) -> Vec<Phase> {
+    tree.nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.parent == parent)
+        .map(|(i, n)| Phase {
+            name: n.name.clone(),
+            duration: n.duration,
+            count: n.count,
+            alloc: if alloc::enabled() {
+                Some(n.alloc)
+            } else {
+                None
+            },
+            children: phases_of(tree, Some(i)),
+        })
+        .collect()
+}
+
+/// Opens a span on the timer installed on this thread, or a span that records
+/// nothing when there is none. Lets solvers time their phases without
+/// passing a timer around.
+pub fn span(name: &str) -> Span {
+    match CURRENT.with(|current| current.borrow().clone()) {
+        Some(timer) => timer.span(name),
+        None => Span {
+            timer: None,
+            index: 0,
+            mark: None,
+            start: Instant::now(),
+        },
+    }
+}
+
+impl Span {
+    /// Ends the phase and returns how long it took.
+    pub fn finish(mut self) -> Duration {
+        self.record()
+    }
+
+    fn record(&mut self) -> Duration {
+        let elapsed = self.start.elapsed();
+        let stats = self.mark.take().map(alloc::stop);
+        if let Some(timer) = self.timer.take() {
+            let mut tree = timer.0.borrow_mut();
+            let node = &mut tree.nodes[self.index];
+            node.duration += elapsed;
+            node.count += 1;
+            if let Some(stats) = stats {
+                node.alloc.merge(stats);
+            }
+            if let Some(pos) = tree.open.iter().rposition(|i| *i == self.index) {
+                tree.open.remove(pos);
+            }
+        }
+        elapsed
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        self.record();
+    }
+}
+
+type Row = (String, Duration, Option<AllocStats>);
+
+fn rows(phases: &[Phase], depth: usize, out: &mut Vec<Row>) {
+    for phase in phases {
+        let mut name = format!("{}{}", "  ".repeat(depth), phase.name);
+        if phase.count > 1 {
+            name += &format!(" ×{}", phase.count);
+        }
+        out.push((name, phase.duration, phase.alloc));
+        rows(&phase.children, depth + 1, out);
+    }
+}
+
+/// Formats phases as an indented tree with aligned durations, followed by the
+/// allocation stats when they were recorded.
+pub struct PhaseTree<'a>(pub &'a [Phase]);
+
+impl fmt::Display for PhaseTree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = Vec::new();
+        rows(self.0, 0, &mut out);
+        let width = out
+            .iter()
+            .map(|(n, _, _)| n.chars().count())
+            .max()
+            .unwrap_or(0);
+        let dur_width = out
+            .iter()
+            .map(|(_, d, _)| fmt_dur(*d).chars().count())
+            .max()
+            .unwrap_or(0);
+        for (name, duration, alloc) in out {
+            let pad = width - name.chars().count();
+            let dur = fmt_dur(duration);
+            write!(f, "{}{}  {}", name, " ".repeat(pad), dur)?;
+            if let Some(alloc) = alloc {
+                let pad = dur_width - dur.chars().count();
+                write!(f, "{}  {}", " ".repeat(pad), alloc)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(phases: &[Phase]) -> Vec<(String, usize)> {
+        phases.iter().map(|p| (p.name.clone(), p.count)).collect()
+    }
+
+    #[test]
+    fn test_nesting() {
+        let timer = Timer::new();
+        {
+            let _parse = timer.span("parse");
+        }
+        {
+            let _part = timer.span("part 2");
+            for _ in 0..5 {
+                let _route = timer.span("route");
+            }
+        }
+        let phases = timer.phases();
+
+        assert_eq!(
+            names(&phases),
+            vec![("parse".to_string(), 1), ("part 2".to_string(), 1)]
+        );
+        assert_eq!(names(&phases[1].children), vec![("route".to_string(), 5)]);
+        assert!(phases[1].duration >= phases[1].children[0].duration);
+    }
+
+    #[test]
+    fn test_finish() {
+        let timer = Timer::new();
+        let span = timer.span("parse");
+        let dur = span.finish();
+
+        assert_eq!(timer.phases()[0].duration, dur);
+        assert_eq!(timer.phases()[0].count, 1);
+    }
+
+    #[test]
+    fn test_installed() {
+        let timer = Timer::new();
+        timer.install();
+        {
+            let _part = timer.span("part 1");
+            let _inner = span("inner");
+        }
+        Timer::uninstall();
+        let _ignored = span("ignored");
+
+        let phases = timer.phases();
+        assert_eq!(names(&phases), vec![("part 1".to_string(), 1)]);
+        assert_eq!(names(&phases[0].children), vec![("inner".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_graft() {
+        let other = Timer::new();
+        {
+            let _part = other.span("part 1");
+            let _inner = other.span("inner");
+        }
+
+        let timer = Timer::new();
+        {
+            let _day = timer.span("day");
+            timer.graft(&other.phases());
+            timer.graft(&other.phases());
+        }
+        let phases = timer.phases();
+
+        assert_eq!(names(&phases[0].children), vec![("part 1".to_string(), 2)]);
+        assert_eq!(
+            names(&phases[0].children[0].children),
+            vec![("inner".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        let phase = |name: &str, ms: u64, count: usize, children: Vec<Phase>| Phase {
+            name: name.to_string(),
+            duration: Duration::from_millis(ms),
+            count,
+            alloc: None,
+            children,
+        };
+        let phases = vec![
+            phase("parse", 2, 1, vec![]),
+            phase("part 2", 5, 1, vec![phase("route", 4, 5, vec![])]),
+        ];
+
+        assert_eq!(
+            PhaseTree(&phases).to_string(),
+            "parse       2ms\npart 2      5ms\n  route ×5  4ms\n"
+        );
+    }
+
+    #[test]
+    fn test_tree_alloc() {
+        let alloc = |allocs, bytes| AllocStats {
+            allocs,
+            bytes,
+            peak: bytes,
+        };
+        let phases = vec![
+            Phase {
+                name: "parse".to_string(),
+                duration: Duration::from_micros(12),
+                count: 1,
+                alloc: Some(alloc(3, 2048)),
+                children: vec![],
+            },
+            Phase {
+                name: "part 1".to_string(),
+                duration: Duration::from_nanos(800),
+                count: 1,
+                alloc: Some(alloc(0, 0)),
+                children: vec![],
+            },
+        ];
+
+        assert_eq!(
+            PhaseTree(&phases).to_string(),
+            "parse   12µs   3 allocs, 2.0 KiB allocated, peak 2.0 KiB\n\
+             part 1  800ns  0 allocs, 0 B allocated, peak 0 B\n"
+        );
+    }
+}
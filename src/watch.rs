@@ -0,0 +1,237 @@
+use crate::examples::{discover, examples_dir};
+use crate::input::{default_path, Source};
+use crate::{fmt_dur, parts_solving, run_day, Day, DayResult, Error, Result, RunOptions};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The modification time and size of each watched file, `None` when missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot(Vec<(PathBuf, Option<(SystemTime, u64)>)>);
+
+/// The input of `day` read from `source`, and its example inputs and answers.
+pub fn watched_files(day: u8, source: &Source) -> Vec<PathBuf> {
+    let mut files = vec![match source {
+        Source::Path(path) => path.clone(),
+        _ => default_path(day),
+    }];
+
+    if let Ok(examples) = discover(&examples_dir()) {
+        for example in examples.into_iter().filter(|e| e.day == day) {
+            files.push(example.path.with_extension("answers"));
+            files.push(example.path);
+        }
+    }
+    files
+}
+
+impl Snapshot {
+    pub fn take(files: &[PathBuf]) -> Snapshot {
+        Snapshot(
+            files
+                .iter()
+                .map(|path| {
+                    let meta = fs::metadata(path).ok();
+                    let stamp = meta.and_then(|m| Some((m.modified().ok()?, m.len())));
+                    (path.clone(), stamp)
+                })
+                .collect(),
+        )
+    }
+
+    /// Files that were added, removed or modified since `earlier`.
+    pub fn changed(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        self.0
+            .iter()
+            .filter(|(path, stamp)| {
+                earlier
+                    .0
+                    .iter()
+                    .find(|(p, _)| p == path)
+                    .is_none_or(|(_, s)| s != stamp)
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+fn fmt_change(before: Duration, after: Duration) -> String {
+    let before_ns = before.as_nanos() as f64;
+    if before_ns == 0.0 {
+        return format!("was {}", fmt_dur(before));
+    }
+    let percent = (after.as_nanos() as f64 - before_ns) / before_ns * 100.0;
+    format!("was {}, {:+.0}%", fmt_dur(before), percent)
+}
+
+/// Describes `current`, with the answers and times of `previous` next to the
+/// ones that differ.
+pub fn describe(previous: Option<&DayResult>, current: &DayResult) -> String {
+    let mut out = String::new();
+
+    let parse = fmt_dur(current.parse_duration);
+    match previous {
+        Some(prev) => writeln!(
+            out,
+            "Parsing took {} ({})",
+            parse,
+            fmt_change(prev.parse_duration, current.parse_duration)
+        ),
+        None => writeln!(out, "Parsing took {}", parse),
+    }
+    .unwrap();
+
+    for part in current.parts.iter() {
+        let prev = previous.and_then(|p| p.parts.iter().find(|q| q.part == part.part));
+        let answer = match prev {
+            Some(prev) if prev.outcome != part.outcome => {
+                format!("{} (was {})", part.answer_text(), prev.answer_text())
+            }
+            _ => part.answer_text(),
+        };
+        let took = match prev {
+            Some(prev) => format!(
+                "{} ({})",
+                fmt_dur(part.duration),
+                fmt_change(prev.duration, part.duration)
+            ),
+            None => fmt_dur(part.duration),
+        };
+        writeln!(out, "Part {}: {}, took {}", part.part, answer, took).unwrap();
+    }
+    out
+}
+
+fn check_examples(day: u8) -> Result<()> {
+    let examples = discover(&examples_dir())?;
+    for example in examples.iter().filter(|e| e.day == day) {
+        let mismatches = example.check()?;
+        if mismatches.is_empty() {
+            println!("Example {}: ok", example.name);
+        }
+        for mismatch in mismatches {
+            println!("Example {}: {}", example.name, mismatch);
+        }
+    }
+    Ok(())
+}
+
+/// Re-runs `day` and its examples whenever one of their files changes, until
+/// the process is stopped. Errors are reported and watching goes on.
+///
+/// Parts that time out are left running, so while any of them is, the day is
+/// not run again. It runs as soon as they all finished.
+pub fn watch(day: &Day, source: &Source) -> Result<()> {
+    if *source == Source::Stdin {
+        return Err(Error::Other("cannot watch stdin".to_string()));
+    }
+
+    let mut previous: Option<DayResult> = None;
+    let mut files = watched_files(day.day, source);
+    let mut snapshot = Snapshot::take(&files);
+    let mut waiting = false;
+    loop {
+        let late = parts_solving();
+        if late > 0 {
+            if !waiting {
+                println!(
+                    "Waiting for {} timed out part(s) to finish before running again",
+                    late
+                );
+            }
+            waiting = true;
+        } else {
+            waiting = false;
+            if let Err(err) = check_examples(day.day) {
+                println!("Examples failed: {}", err);
+            }
+            match run_day(day, source, RunOptions::default()) {
+                Ok(result) => {
+                    print!("{}", describe(previous.as_ref(), &result));
+                    previous = Some(result);
+                }
+                Err(err) => println!("Error: {}", err),
+            }
+            println!("Watching {} file(s), press Ctrl-C to stop", files.len());
+        }
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            files = watched_files(day.day, source);
+            let current = Snapshot::take(&files);
+            let changed = current.changed(&snapshot);
+            snapshot = current;
+            if !changed.is_empty() {
+                for path in changed {
+                    println!("\nChanged {}", path.display());
+                }
+                break;
+            }
+            if waiting && parts_solving() == 0 {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Outcome, PartResult};
+
+    fn result(answer: i64, ms: u64) -> DayResult {
+        DayResult {
+            day: 1,
+            parse_duration: Duration::from_millis(ms),
+            parts: vec![PartResult {
+                part: 1,
+                outcome: Outcome::Answer(Answer::Number(answer.into())),
+                duration: Duration::from_millis(ms),
+            }],
+            phases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(None, &result(7, 2)),
+            "Parsing took 2ms\nPart 1: 7, took 2ms\n"
+        );
+        assert_eq!(
+            describe(Some(&result(5, 2)), &result(7, 3)),
+            "Parsing took 3ms (was 2ms, +50%)\nPart 1: 7 (was 5), took 3ms (was 2ms, +50%)\n"
+        );
+    }
+
+    #[test]
+    fn test_changed() {
+        let path = std::env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        let files = vec![path.clone()];
+        let _ = fs::remove_file(&path);
+
+        let missing = Snapshot::take(&files);
+        fs::write(&path, "1\n").unwrap();
+        let created = Snapshot::take(&files);
+        fs::write(&path, "1\n2\n").unwrap();
+        let modified = Snapshot::take(&files);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(created.changed(&missing), files);
+        assert_eq!(modified.changed(&created), files);
+        assert!(modified.changed(&modified).is_empty());
+    }
+
+    #[test]
+    fn test_watched_files() {
+        let files = watched_files(3, &Source::Default);
+
+        assert_eq!(files[0], default_path(3));
+        assert!(files.contains(&examples_dir().join("day03/example.txt")));
+    }
+}
@@ -0,0 +1,21 @@
+use advent_of_code::examples::{discover, examples_dir};
+
+#[test]
+fn test_examples() {
+    let examples = discover(&examples_dir()).unwrap();
+    assert!(!examples.is_empty(), "no examples found");
+
+    let mut failures = Vec::new();
+    for example in examples.iter() {
+        match example.check() {
+            Ok(mismatches) => failures.extend(
+                mismatches
+                    .iter()
+                    .map(|m| format!("{} ({}):\n{}", example.name, example.path.display(), m)),
+            ),
+            Err(err) => failures.push(format!("{}: {}", example.path.display(), err)),
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}